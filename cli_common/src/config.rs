@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair},
+};
+use std::{error::Error, fs, path::PathBuf, str::FromStr};
+
+/// RPC url used when neither the flags nor the config file name one
+pub const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+
+/// Fields of the Solana CLI config file the tool cares about, the others are ignored
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SolanaConfig {
+    pub json_rpc_url: Option<String>,
+    pub keypair_path: Option<String>,
+    pub commitment: Option<String>,
+}
+
+impl SolanaConfig {
+    pub fn parse(content: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(content)
+    }
+
+    /// Read the config at `path`, or at the Solana CLI default location when unset. A missing
+    /// default config is not an error, a missing explicit one is
+    pub fn load(path: Option<&PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let (path, explicit) = match path {
+            Some(path) => (path.clone(), true),
+            None => match solana_config_dir() {
+                Some(dir) => (dir.join("cli").join("config.yml"), false),
+                None => return Ok(Self::default()),
+            },
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => Ok(Self::parse(&content)
+                .map_err(|e| format!("invalid config {}: {}", path.display(), e))?),
+            Err(_) if !explicit => Ok(Self::default()),
+            Err(e) => Err(format!("cannot read config {}: {}", path.display(), e).into()),
+        }
+    }
+
+    /// Client of the cluster at `url`, else at the url of the config, else at
+    /// [DEFAULT_RPC_URL]. Requests use the commitment of the config, confirmed when unset
+    pub fn rpc_client(&self, url: Option<String>) -> Result<RpcClient, Box<dyn Error>> {
+        let url = url
+            .or_else(|| self.json_rpc_url.clone())
+            .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+        let commitment = match &self.commitment {
+            Some(commitment) => CommitmentConfig::from_str(commitment)
+                .map_err(|_| format!("invalid commitment {}", commitment))?,
+            None => CommitmentConfig::confirmed(),
+        };
+        Ok(RpcClient::new_with_commitment(url, commitment))
+    }
+
+    /// Keypair at `path`, else at the keypair path of the config, else at
+    /// [default_keypair_path]
+    pub fn read_keypair(&self, path: Option<PathBuf>) -> Result<Keypair, Box<dyn Error>> {
+        let path = path
+            .or_else(|| self.keypair_path.as_ref().map(PathBuf::from))
+            .or_else(default_keypair_path)
+            .ok_or("missing --keypair")?;
+        Ok(read_keypair_file(&path)
+            .map_err(|e| format!("cannot read keypair {}: {}", path.display(), e))?)
+    }
+}
+
+/// `~/.config/solana`, where the Solana CLI keeps its config and default keypair
+pub fn solana_config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("solana"))
+}
+
+/// Keypair used when neither the flags nor the config file name one
+pub fn default_keypair_path() -> Option<PathBuf> {
+    solana_config_dir().map(|dir| dir.join("id.json"))
+}
@@ -0,0 +1,8 @@
+//! Helpers shared by the command line tools of the workspace
+
+pub mod config;
+
+pub use config::{default_keypair_path, solana_config_dir, SolanaConfig, DEFAULT_RPC_URL};
+
+#[cfg(test)]
+mod test;
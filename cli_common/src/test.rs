@@ -0,0 +1,57 @@
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::path::PathBuf;
+
+use super::*;
+
+#[test]
+fn test_solana_config() {
+    let config = SolanaConfig::parse(
+        "---\n\
+         json_rpc_url: \"https://api.devnet.solana.com\"\n\
+         websocket_url: \"\"\n\
+         keypair_path: /home/user/.config/solana/id.json\n\
+         address_labels:\n  \"11111111111111111111111111111111\": System Program\n\
+         commitment: finalized\n",
+    )
+    .unwrap();
+    assert_eq!(
+        config,
+        SolanaConfig {
+            json_rpc_url: Some("https://api.devnet.solana.com".to_string()),
+            keypair_path: Some("/home/user/.config/solana/id.json".to_string()),
+            commitment: Some("finalized".to_string()),
+        }
+    );
+    assert_eq!(SolanaConfig::parse("{}").unwrap(), SolanaConfig::default());
+
+    // an explicit config that doesn't exist is an error, the default one is optional
+    let missing = PathBuf::from("/nonexistent/config.yml");
+    assert!(SolanaConfig::load(Some(&missing)).is_err());
+}
+
+#[test]
+fn test_rpc_client() {
+    let config = SolanaConfig {
+        json_rpc_url: Some("https://api.devnet.solana.com".to_string()),
+        keypair_path: None,
+        commitment: Some("finalized".to_string()),
+    };
+    let client = config.rpc_client(None).unwrap();
+    assert_eq!(client.url(), "https://api.devnet.solana.com");
+    assert_eq!(client.commitment(), CommitmentConfig::finalized());
+    // the url flag wins over the config
+    let client = config
+        .rpc_client(Some("http://localhost:8899".to_string()))
+        .unwrap();
+    assert_eq!(client.url(), "http://localhost:8899");
+
+    let client = SolanaConfig::default().rpc_client(None).unwrap();
+    assert_eq!(client.url(), DEFAULT_RPC_URL);
+    assert_eq!(client.commitment(), CommitmentConfig::confirmed());
+
+    let config = SolanaConfig {
+        commitment: Some("eventually".to_string()),
+        ..SolanaConfig::default()
+    };
+    assert!(config.rpc_client(None).is_err());
+}
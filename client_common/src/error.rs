@@ -0,0 +1,36 @@
+use program_common::ErrorCode;
+use solana_client::client_error::ClientError as RpcClientError;
+use solana_sdk::{
+    instruction::InstructionError, program_error::ProgramError, pubkey::Pubkey,
+    transaction::TransactionError,
+};
+use thiserror::Error;
+
+/// Error of a client of the program whose custom errors are `E`
+#[derive(Debug, Error)]
+pub enum ClientError<E: ErrorCode> {
+    /// The program rejected the transaction
+    #[error("program error: {0}")]
+    Program(E),
+    /// The cluster or the runtime failed the request, for any other reason
+    #[error(transparent)]
+    Rpc(RpcClientError),
+    #[error("no program account at {0}")]
+    AccountNotFound(Pubkey),
+    #[error("account {0} is not of the expected type: {1}")]
+    InvalidAccount(Pubkey, ProgramError),
+}
+
+impl<E: ErrorCode> From<RpcClientError> for ClientError<E> {
+    fn from(e: RpcClientError) -> Self {
+        match e.get_transaction_error() {
+            Some(TransactionError::InstructionError(_, InstructionError::Custom(code))) => {
+                match E::from_code(code) {
+                    Some(error) => ClientError::Program(error),
+                    None => ClientError::Rpc(e),
+                }
+            }
+            _ => ClientError::Rpc(e),
+        }
+    }
+}
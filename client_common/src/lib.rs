@@ -0,0 +1,8 @@
+//! Helpers shared by the async clients of the programs
+
+pub mod error;
+
+pub use error::ClientError;
+
+#[cfg(test)]
+mod test;
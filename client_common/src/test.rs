@@ -0,0 +1,60 @@
+use program_common::error_code;
+use solana_client::{
+    client_error::{ClientError as RpcClientError, ClientErrorKind},
+    rpc_request::{RpcError, RpcResponseErrorData},
+    rpc_response::RpcSimulateTransactionResult,
+};
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+use super::*;
+
+error_code! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+    enum TestError {
+        #[error("first")]
+        First,
+        #[error("second")]
+        Second,
+    }
+}
+
+fn custom_error(code: u32) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(code))
+}
+
+#[test]
+fn test_decode_program_error() {
+    let error = ClientError::<TestError>::from(RpcClientError::from(custom_error(1)));
+    assert!(matches!(error, ClientError::Program(TestError::Second)));
+    assert_eq!(error.to_string(), "program error: second");
+
+    // preflight failures carry the error in the simulation result
+    let preflight = RpcClientError::from(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+        code: -32002,
+        message: "Transaction simulation failed".to_string(),
+        data: RpcResponseErrorData::SendTransactionPreflightFailure(RpcSimulateTransactionResult {
+            err: Some(custom_error(0)),
+            logs: None,
+            accounts: None,
+            units_consumed: None,
+            return_data: None,
+            inner_instructions: None,
+        }),
+    }));
+    assert!(matches!(
+        ClientError::from(preflight),
+        ClientError::Program(TestError::First)
+    ));
+
+    // codes the program doesn't define and runtime errors are left as they are
+    for error in [
+        custom_error(2),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId),
+        TransactionError::AccountNotFound,
+    ] {
+        assert!(matches!(
+            ClientError::<TestError>::from(RpcClientError::from(error)),
+            ClientError::Rpc(_)
+        ));
+    }
+}
@@ -0,0 +1,238 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use cli_common::SolanaConfig;
+use counter_client::{BoundPolicy, CounterAccount, CounterBounds, CounterClient};
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use std::{error::Error, path::PathBuf, process::ExitCode};
+
+/// Operate counters of the counter program
+#[derive(Parser, Debug)]
+#[command(name = "counter-cli", version)]
+struct Cli {
+    /// Solana CLI config file [default: ~/.config/solana/cli/config.yml]
+    #[arg(short = 'C', long, global = true)]
+    config: Option<PathBuf>,
+    /// RPC url, overrides the config file
+    #[arg(short, long, global = true)]
+    url: Option<String>,
+    /// Keypair of the counter authority, pays every fee, overrides the config file
+    #[arg(short, long, global = true)]
+    keypair: Option<PathBuf>,
+    /// Address of the deployed counter program
+    #[arg(long, env = "COUNTER_PROGRAM_ID", global = true)]
+    program_id: Option<Pubkey>,
+    /// Print results as text or as one JSON object
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create the counter of the keypair for a seed
+    Init {
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+        value: i64,
+        /// Lowest value the counter may hold
+        #[arg(long, requires = "max", allow_hyphen_values = true)]
+        min: Option<i64>,
+        /// Highest value the counter may hold
+        #[arg(long, requires = "min", allow_hyphen_values = true)]
+        max: Option<i64>,
+        /// Clamp changes to the bounds instead of rejecting them
+        #[arg(long, requires = "min")]
+        saturate: bool,
+    },
+    /// Add one to a counter
+    Inc(CounterArgs),
+    /// Remove one from a counter
+    Dec(CounterArgs),
+    /// Print a counter
+    Show(CounterArgs),
+    /// Close a counter and reclaim its rent
+    Close {
+        #[command(flatten)]
+        counter: CounterArgs,
+        /// Receives the rent of the counter [default: the keypair]
+        #[arg(long)]
+        destination: Option<Pubkey>,
+    },
+}
+
+/// Which counter a command works on
+#[derive(Args, Debug)]
+struct CounterArgs {
+    /// Seed of the counter, derived from the keypair
+    #[arg(long, default_value_t = 0, conflicts_with = "counter")]
+    seed: u64,
+    /// Address of the counter, instead of deriving it from the seed
+    #[arg(long)]
+    counter: Option<Pubkey>,
+}
+
+impl CounterArgs {
+    fn address(&self, client: &CounterClient) -> Pubkey {
+        self.counter
+            .unwrap_or_else(|| client.counter_address(self.seed))
+    }
+}
+
+fn counter_json(address: &Pubkey, counter: &CounterAccount) -> Value {
+    json!({
+        "address": address.to_string(),
+        "count": counter.count,
+        "authority": counter.authority.to_string(),
+        "version": counter.version,
+        "bounds": counter.bounds.map(|bounds| json!({
+            "min": bounds.min,
+            "max": bounds.max,
+            "policy": policy_name(bounds.policy),
+        })),
+    })
+}
+
+fn counter_text(address: &Pubkey, counter: &CounterAccount) -> String {
+    let bounds = match counter.bounds {
+        Some(bounds) => format!(
+            "[{}, {}] ({})",
+            bounds.min,
+            bounds.max,
+            policy_name(bounds.policy)
+        ),
+        None => "none".to_string(),
+    };
+    format!(
+        "Counter: {}\nCount: {}\nAuthority: {}\nBounds: {}",
+        address, counter.count, counter.authority, bounds
+    )
+}
+
+fn policy_name(policy: BoundPolicy) -> &'static str {
+    match policy {
+        BoundPolicy::Reject => "reject",
+        BoundPolicy::Saturate => "saturate",
+    }
+}
+
+fn print(output: OutputFormat, json: Value, text: String) {
+    match output {
+        OutputFormat::Text => println!("{}", text),
+        OutputFormat::Json => println!("{}", json),
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let config = SolanaConfig::load(cli.config.as_ref())?;
+    let program_id = cli
+        .program_id
+        .ok_or("missing --program-id or COUNTER_PROGRAM_ID")?;
+
+    // showing a counter by address never signs, it doesn't need the keypair
+    let keypair = match &cli.command {
+        Command::Show(CounterArgs {
+            counter: Some(_), ..
+        }) => Keypair::new(),
+        _ => config.read_keypair(cli.keypair)?,
+    };
+    let client = CounterClient::new(config.rpc_client(cli.url)?, program_id, keypair);
+
+    match cli.command {
+        Command::Init {
+            seed,
+            value,
+            min,
+            max,
+            saturate,
+        } => {
+            let bounds = min.zip(max).map(|(min, max)| CounterBounds {
+                min,
+                max,
+                policy: if saturate {
+                    BoundPolicy::Saturate
+                } else {
+                    BoundPolicy::Reject
+                },
+            });
+            let created = client.create(seed, value, bounds).await?;
+            print(
+                cli.output,
+                json!({
+                    "address": created.address.to_string(),
+                    "signature": created.signature.to_string(),
+                }),
+                format!(
+                    "Counter: {}\nSignature: {}",
+                    created.address, created.signature
+                ),
+            );
+        }
+        Command::Inc(counter) => {
+            let address = counter.address(&client);
+            let update = client.increment(&address).await?;
+            print(
+                cli.output,
+                json!({ "count": update.count, "signature": update.signature.to_string() }),
+                format!("Count: {}\nSignature: {}", update.count, update.signature),
+            );
+        }
+        Command::Dec(counter) => {
+            let address = counter.address(&client);
+            let update = client.decrement(&address).await?;
+            print(
+                cli.output,
+                json!({ "count": update.count, "signature": update.signature.to_string() }),
+                format!("Count: {}\nSignature: {}", update.count, update.signature),
+            );
+        }
+        Command::Show(counter) => {
+            let address = counter.address(&client);
+            let account = client.fetch(&address).await?;
+            print(
+                cli.output,
+                counter_json(&address, &account),
+                counter_text(&address, &account),
+            );
+        }
+        Command::Close {
+            counter,
+            destination,
+        } => {
+            let address = counter.address(&client);
+            let destination = destination.unwrap_or_else(|| client.authority().pubkey());
+            let signature = client.close(&address, &destination).await?;
+            print(
+                cli.output,
+                json!({
+                    "address": address.to_string(),
+                    "destination": destination.to_string(),
+                    "signature": signature.to_string(),
+                }),
+                format!("Closed: {}\nSignature: {}", address, signature),
+            );
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,68 @@
+use clap::CommandFactory;
+
+use super::*;
+
+#[test]
+fn test_cli_definition() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn test_parse_init() {
+    let cli = Cli::try_parse_from([
+        "counter-cli",
+        "init",
+        "--seed",
+        "2",
+        "--value",
+        "-5",
+        "--min",
+        "-10",
+        "--max",
+        "10",
+        "--saturate",
+        "--output",
+        "json",
+    ])
+    .unwrap();
+    assert_eq!(cli.output, OutputFormat::Json);
+    assert!(matches!(
+        cli.command,
+        Command::Init {
+            seed: 2,
+            value: -5,
+            min: Some(-10),
+            max: Some(10),
+            saturate: true,
+        }
+    ));
+
+    // bounds go by pair
+    assert!(Cli::try_parse_from(["counter-cli", "init", "--min", "0"]).is_err());
+    assert!(Cli::try_parse_from(["counter-cli", "init", "--saturate"]).is_err());
+}
+
+#[test]
+fn test_parse_counter_args() {
+    let counter = Pubkey::new_unique();
+    let cli =
+        Cli::try_parse_from(["counter-cli", "show", "--counter", &counter.to_string()]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Command::Show(CounterArgs {
+            seed: 0,
+            counter: Some(address),
+        }) if address == counter
+    ));
+
+    // a counter is named either by seed or by address
+    assert!(Cli::try_parse_from([
+        "counter-cli",
+        "inc",
+        "--seed",
+        "1",
+        "--counter",
+        &counter.to_string(),
+    ])
+    .is_err());
+}
@@ -0,0 +1,51 @@
+use counter_client::CounterClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+
+#[tokio::main]
+async fn main() {
+    let keypair = read_keypair_file("../target/deploy/counter_program-keypair.json").unwrap();
+    let program_pubkey = keypair.pubkey();
+
+    // local validator unless another RPC url is given
+    let rpc_url = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "http://127.0.0.1:8899".to_string());
+    let rpc = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+
+    // Generate a new keypair for the payer and fund it
+    let payer = Keypair::new();
+    let airdrop_amount = 1_000_000_000; // 1 SOL
+    let signature = rpc
+        .request_airdrop(&payer.pubkey(), airdrop_amount)
+        .await
+        .expect("Failed to request airdrop");
+    rpc.poll_for_signature(&signature)
+        .await
+        .expect("Airdrop was not confirmed");
+
+    let client = CounterClient::new(rpc, program_pubkey, payer);
+
+    let created = match client.create(0, 0, None).await {
+        Ok(created) => created,
+        Err(err) => {
+            eprintln!("Error creating counter: {}", err);
+            return;
+        }
+    };
+    println!(
+        "Counter {} created, signature: {}",
+        created.address, created.signature
+    );
+
+    match client.increment(&created.address).await {
+        Ok(update) => println!(
+            "Counter incremented to {}, signature: {}",
+            update.count, update.signature
+        ),
+        Err(err) => eprintln!("Error incrementing counter: {}", err),
+    }
+}
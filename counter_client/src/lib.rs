@@ -0,0 +1,197 @@
+//! Async client of the counter program
+
+pub use counter_program::state::{BoundPolicy, CounterAccount, CounterBounds};
+
+use counter_program::{
+    error::CustomError,
+    events::{CounterChanged, CounterEvent},
+    find_counter_address, instruction,
+};
+use program_common::Event;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use solana_transaction_status::UiTransactionEncoding;
+
+/// Error of every request of [CounterClient]
+pub type ClientError = client_common::ClientError<CustomError>;
+
+/// Counter created by [CounterClient::create]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatedCounter {
+    pub address: Pubkey,
+    pub signature: Signature,
+}
+
+/// Counter change confirmed by the cluster, with the value the transaction left the counter at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CounterUpdate {
+    pub signature: Signature,
+    pub count: i64,
+}
+
+/// Send counter instructions through `rpc`, `authority` pays for every transaction and owns
+/// the counters it creates
+pub struct CounterClient {
+    rpc: RpcClient,
+    program_id: Pubkey,
+    authority: Keypair,
+}
+
+impl CounterClient {
+    pub fn new(rpc: RpcClient, program_id: Pubkey, authority: Keypair) -> Self {
+        Self {
+            rpc,
+            program_id,
+            authority,
+        }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub fn authority(&self) -> &Keypair {
+        &self.authority
+    }
+
+    /// Address of the counter of the client authority for `seed`
+    pub fn counter_address(&self, seed: u64) -> Pubkey {
+        find_counter_address(&self.program_id, &self.authority.pubkey(), seed).0
+    }
+
+    pub async fn create(
+        &self,
+        seed: u64,
+        initial_value: i64,
+        bounds: Option<CounterBounds>,
+    ) -> Result<CreatedCounter, ClientError> {
+        let instruction = instruction::init_counter(
+            &self.program_id,
+            &self.authority.pubkey(),
+            seed,
+            initial_value,
+            bounds,
+        );
+        let signature = self.send(instruction).await?;
+        Ok(CreatedCounter {
+            address: self.counter_address(seed),
+            signature,
+        })
+    }
+
+    /// Add one to `counter`
+    pub async fn increment(&self, counter: &Pubkey) -> Result<CounterUpdate, ClientError> {
+        let instruction =
+            instruction::increment(&self.program_id, counter, &self.authority.pubkey());
+        self.update(counter, instruction).await
+    }
+
+    /// Remove one from `counter`
+    pub async fn decrement(&self, counter: &Pubkey) -> Result<CounterUpdate, ClientError> {
+        let instruction =
+            instruction::decrement(&self.program_id, counter, &self.authority.pubkey());
+        self.update(counter, instruction).await
+    }
+
+    /// Close `counter` and send its rent to `destination`
+    pub async fn close(
+        &self,
+        counter: &Pubkey,
+        destination: &Pubkey,
+    ) -> Result<Signature, ClientError> {
+        let instruction = instruction::close_counter(
+            &self.program_id,
+            counter,
+            &self.authority.pubkey(),
+            destination,
+        );
+        self.send(instruction).await
+    }
+
+    pub async fn fetch(&self, counter: &Pubkey) -> Result<CounterAccount, ClientError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(counter, self.rpc.commitment())
+            .await?
+            .value
+            .filter(|account| account.owner == self.program_id)
+            .ok_or(ClientError::AccountNotFound(*counter))?;
+        CounterAccount::unpack(&account.data).map_err(|e| ClientError::InvalidAccount(*counter, e))
+    }
+
+    async fn update(
+        &self,
+        counter: &Pubkey,
+        instruction: Instruction,
+    ) -> Result<CounterUpdate, ClientError> {
+        let signature = self.send(instruction).await?;
+
+        // the count comes from the event of the transaction itself, reading the account back
+        // would show any change landing in between
+        let commitment = if self.rpc.commitment().is_at_least_confirmed() {
+            self.rpc.commitment()
+        } else {
+            CommitmentConfig::confirmed()
+        };
+        self.rpc
+            .poll_for_signature_with_commitment(&signature, commitment)
+            .await?;
+        let transaction = self
+            .rpc
+            .get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(commitment),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+        let logs: Vec<String> = transaction
+            .transaction
+            .meta
+            .and_then(|meta| meta.log_messages.into())
+            .unwrap_or_default();
+        let count = CounterEvent::decode_logs(&self.program_id, &logs)
+            .into_iter()
+            .find_map(|event| match event {
+                CounterEvent::CounterChanged(CounterChanged {
+                    counter: changed,
+                    new_value,
+                    ..
+                }) if changed == *counter => Some(new_value),
+                _ => None,
+            });
+        let count = match count {
+            Some(count) => count,
+            // the logs got truncated before the event
+            None => self.fetch(counter).await?.count,
+        };
+        Ok(CounterUpdate { signature, count })
+    }
+
+    async fn send(&self, instruction: Instruction) -> Result<Signature, ClientError> {
+        let recent_blockhash = self.rpc.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.authority.pubkey()),
+            &[&self.authority],
+            recent_blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx).await?)
+    }
+}
+
+#[cfg(test)]
+mod test;
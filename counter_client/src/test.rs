@@ -0,0 +1,43 @@
+use counter_program::error::CustomError;
+use solana_client::client_error::ClientError as RpcClientError;
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+use super::*;
+
+fn custom_error(code: u32) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(code))
+}
+
+#[test]
+fn test_decode_program_error() {
+    let error = ClientError::from(RpcClientError::from(custom_error(6)));
+    assert!(matches!(
+        error,
+        ClientError::Program(CustomError::AboveMaximum)
+    ));
+
+    // codes the program doesn't define and runtime errors are left as they are
+    for error in [
+        custom_error(u32::MAX),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId),
+        TransactionError::AccountNotFound,
+    ] {
+        assert!(matches!(
+            ClientError::from(RpcClientError::from(error)),
+            ClientError::Rpc(_)
+        ));
+    }
+}
+
+#[test]
+fn test_counter_address() {
+    let program_id = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let (expected, _) = find_counter_address(&program_id, &authority.pubkey(), 7);
+    let client = CounterClient::new(
+        RpcClient::new("http://127.0.0.1:8899".to_string()),
+        program_id,
+        authority,
+    );
+    assert_eq!(client.counter_address(7), expected);
+}
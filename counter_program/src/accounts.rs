@@ -0,0 +1,125 @@
+use crate::error::CustomError;
+use program_common::{check_distinct, check_owner, check_program, check_signer, check_writable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// Accounts of InitCounter
+pub struct InitCounterAccounts<'a, 'info> {
+    /// Counter PDA to create, checked against the seed by the processor
+    pub counter: &'a AccountInfo<'info>,
+    /// Pays for the counter and becomes its authority
+    pub payer: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> InitCounterAccounts<'a, 'info> {
+    pub fn try_from(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let counter = next_account_info(accounts_iter)?;
+        let payer = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        check_writable(counter, CustomError::AccountNotWritable)?;
+        check_signer(payer, CustomError::MissingAuthoritySignature)?;
+        check_writable(payer, CustomError::AccountNotWritable)?;
+        check_program(system_program, &system_program::ID)?;
+        Ok(Self {
+            counter,
+            payer,
+            system_program,
+        })
+    }
+}
+
+/// Accounts of every instruction changing an existing counter: IncCounter, DecCounter,
+/// IncrementBy, DecrementBy, Reset, Set and SetAuthority
+pub struct UpdateCounterAccounts<'a, 'info> {
+    pub counter: &'a AccountInfo<'info>,
+    /// Must sign, checked against the counter authority by the processor
+    pub authority: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> UpdateCounterAccounts<'a, 'info> {
+    pub fn try_from(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let counter = next_account_info(accounts_iter)?;
+        let authority = next_account_info(accounts_iter)?;
+
+        check_owner(counter, program_id)?;
+        check_writable(counter, CustomError::AccountNotWritable)?;
+        check_signer(authority, CustomError::MissingAuthoritySignature)?;
+        Ok(Self { counter, authority })
+    }
+}
+
+/// Accounts of CloseCounter
+pub struct CloseCounterAccounts<'a, 'info> {
+    pub counter: &'a AccountInfo<'info>,
+    /// Must sign, checked against the counter authority by the processor
+    pub authority: &'a AccountInfo<'info>,
+    /// Receives the lamports of the counter
+    pub destination: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> CloseCounterAccounts<'a, 'info> {
+    pub fn try_from(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let counter = next_account_info(accounts_iter)?;
+        let authority = next_account_info(accounts_iter)?;
+        let destination = next_account_info(accounts_iter)?;
+
+        check_owner(counter, program_id)?;
+        check_writable(counter, CustomError::AccountNotWritable)?;
+        check_signer(authority, CustomError::MissingAuthoritySignature)?;
+        check_writable(destination, CustomError::AccountNotWritable)?;
+        check_distinct(counter, destination, CustomError::DuplicateAccount)?;
+        Ok(Self {
+            counter,
+            authority,
+            destination,
+        })
+    }
+}
+
+/// Accounts of Migrate
+pub struct MigrateCounterAccounts<'a, 'info> {
+    /// Legacy keypair counter, it signs to prove who owns it
+    pub counter: &'a AccountInfo<'info>,
+    /// Becomes the counter authority and pays for the extra rent
+    pub authority: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> MigrateCounterAccounts<'a, 'info> {
+    pub fn try_from(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let counter = next_account_info(accounts_iter)?;
+        let authority = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        check_owner(counter, program_id)?;
+        check_signer(counter, CustomError::MissingAuthoritySignature)?;
+        check_writable(counter, CustomError::AccountNotWritable)?;
+        check_signer(authority, CustomError::MissingAuthoritySignature)?;
+        check_writable(authority, CustomError::AccountNotWritable)?;
+        check_program(system_program, &system_program::ID)?;
+        Ok(Self {
+            counter,
+            authority,
+            system_program,
+        })
+    }
+}
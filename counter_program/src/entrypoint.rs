@@ -1,14 +1,23 @@
-use instructions::{CounterInstruction, Unpack};
-use processor::{process_change_counter, process_initialize_counter};
-use solana_program::entrypoint;
+use instructions::CounterInstruction;
+use processor::{
+    process_change_counter, process_close_counter, process_initialize_counter,
+    process_migrate_counter, process_set_authority, process_set_counter,
+};
+use program_common::Unpack;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 
+pub mod accounts;
 pub mod error;
+pub mod events;
+pub mod instruction;
 pub mod instructions;
 pub mod processor;
 pub mod state;
 
-entrypoint!(entrypoints);
+pub use state::find_counter_address;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(entrypoints);
 pub fn entrypoints(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -16,9 +25,26 @@ pub fn entrypoints(
 ) -> ProgramResult {
     let instruction = CounterInstruction::unpack(instruction_data)?;
     match instruction {
-        CounterInstruction::InitCounter => process_initialize_counter(program_id, accounts, 0),
-        CounterInstruction::IncCounter => process_change_counter(program_id, accounts, true),
-        CounterInstruction::DecCounter => process_change_counter(program_id, accounts, false),
+        CounterInstruction::InitCounter {
+            initial_value,
+            seed,
+            bounds,
+        } => process_initialize_counter(program_id, accounts, initial_value, seed, bounds),
+        CounterInstruction::IncCounter => process_change_counter(program_id, accounts, true, 1),
+        CounterInstruction::DecCounter => process_change_counter(program_id, accounts, false, 1),
+        CounterInstruction::SetAuthority { new_authority } => {
+            process_set_authority(program_id, accounts, new_authority)
+        }
+        CounterInstruction::IncrementBy { amount } => {
+            process_change_counter(program_id, accounts, true, amount)
+        }
+        CounterInstruction::DecrementBy { amount } => {
+            process_change_counter(program_id, accounts, false, amount)
+        }
+        CounterInstruction::Reset => process_set_counter(program_id, accounts, 0),
+        CounterInstruction::Set { value } => process_set_counter(program_id, accounts, value),
+        CounterInstruction::CloseCounter => process_close_counter(program_id, accounts),
+        CounterInstruction::Migrate => process_migrate_counter(program_id, accounts),
     }
 }
 
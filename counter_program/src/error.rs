@@ -1,16 +1,43 @@
-use solana_program::program_error::ProgramError;
+use program_common::AccountHeaderError;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
-pub enum CustomError {
-    #[error("Operation will overflow")]
-    Overflow,
-    #[error("Operation will underflow")]
-    Underflow,
+program_common::error_code! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+    pub enum CustomError {
+        #[error("Operation will overflow")]
+        Overflow,
+        #[error("Operation will underflow")]
+        Underflow,
+        #[error("Counter authority did not sign the transaction")]
+        MissingAuthoritySignature,
+        #[error("Signer is not the counter authority")]
+        InvalidAuthority,
+        #[error("Counter account does not match the authority and seed")]
+        InvalidCounterAddress,
+        #[error("Counter bounds are empty or exclude the initial value")]
+        InvalidBounds,
+        #[error("Counter would go above its maximum")]
+        AboveMaximum,
+        #[error("Counter would go below its minimum")]
+        BelowMinimum,
+        #[error("Account is not a counter")]
+        InvalidAccountDiscriminator,
+        #[error("Counter layout version is not supported")]
+        UnsupportedAccountVersion,
+        #[error("Counter already uses the current layout")]
+        AlreadyMigrated,
+        #[error("Account must be writable")]
+        AccountNotWritable,
+        #[error("The same account was passed twice")]
+        DuplicateAccount,
+    }
 }
 
-impl From<CustomError> for ProgramError {
-    fn from(e: CustomError) -> Self {
-        ProgramError::Custom(e as u32)
+impl From<AccountHeaderError> for CustomError {
+    fn from(e: AccountHeaderError) -> Self {
+        match e {
+            AccountHeaderError::InvalidDiscriminator => CustomError::InvalidAccountDiscriminator,
+            AccountHeaderError::UnsupportedVersion => CustomError::UnsupportedAccountVersion,
+        }
     }
 }
@@ -0,0 +1,59 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use program_common::Event;
+use solana_program::pubkey::Pubkey;
+
+/// Emitted by InitCounter
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CounterCreated {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub value: i64,
+}
+
+/// Emitted by every instruction changing the count: IncCounter, DecCounter, IncrementBy,
+/// DecrementBy, Reset and Set
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CounterChanged {
+    pub counter: Pubkey,
+    pub old_value: i64,
+    pub new_value: i64,
+}
+
+/// Emitted by SetAuthority
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuthorityChanged {
+    pub counter: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+/// Emitted by CloseCounter
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CounterClosed {
+    pub counter: Pubkey,
+    pub destination: Pubkey,
+    /// Rent sent to the destination
+    pub lamports: u64,
+}
+
+/// Emitted by Migrate
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CounterMigrated {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub value: i64,
+}
+
+/// Every event logged by the program, the variant index leads the encoded data
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+#[borsh(use_discriminant = true)]
+#[repr(u8)]
+pub enum CounterEvent {
+    CounterCreated(CounterCreated) = 0,
+    CounterChanged(CounterChanged) = 1,
+    AuthorityChanged(AuthorityChanged) = 2,
+    CounterClosed(CounterClosed) = 3,
+    CounterMigrated(CounterMigrated) = 4,
+}
+
+impl Event for CounterEvent {}
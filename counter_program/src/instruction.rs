@@ -0,0 +1,166 @@
+use crate::{
+    instructions::CounterInstruction,
+    state::{find_counter_address, CounterBounds},
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// Create the counter derived from `authority` and `seed`, `authority` pays for the account
+pub fn init_counter(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    seed: u64,
+    initial_value: i64,
+    bounds: Option<CounterBounds>,
+) -> Instruction {
+    let (counter, _) = find_counter_address(program_id, authority, seed);
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::InitCounter {
+            initial_value,
+            seed,
+            bounds,
+        },
+        vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Add one to `counter`
+pub fn increment(program_id: &Pubkey, counter: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::IncCounter,
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Subtract one from `counter`
+pub fn decrement(program_id: &Pubkey, counter: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::DecCounter,
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Add `amount` to `counter`
+pub fn increment_by(
+    program_id: &Pubkey,
+    counter: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::IncrementBy { amount },
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Subtract `amount` from `counter`
+pub fn decrement_by(
+    program_id: &Pubkey,
+    counter: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::DecrementBy { amount },
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Put `counter` back to zero
+pub fn reset(program_id: &Pubkey, counter: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::Reset,
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Overwrite `counter` with `value`
+pub fn set(program_id: &Pubkey, counter: &Pubkey, authority: &Pubkey, value: i64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::Set { value },
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Close `counter` and send its lamports to `destination`
+pub fn close_counter(
+    program_id: &Pubkey,
+    counter: &Pubkey,
+    authority: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::CloseCounter,
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*destination, false),
+        ],
+    )
+}
+
+/// Hand `counter` over to `new_authority`
+pub fn set_authority(
+    program_id: &Pubkey,
+    counter: &Pubkey,
+    authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::SetAuthority {
+            new_authority: *new_authority,
+        },
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Move the legacy keypair `counter` to the current layout, `authority` becomes its authority
+/// and pays for the extra rent
+pub fn migrate_counter(program_id: &Pubkey, counter: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &CounterInstruction::Migrate,
+        vec![
+            AccountMeta::new(*counter, true),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
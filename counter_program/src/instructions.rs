@@ -1,24 +1,41 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::program_error::ProgramError;
+use program_common::Unpack;
+use solana_program::pubkey::Pubkey;
 
-pub trait Unpack {
-    fn unpack(input: &[u8]) -> Result<Self, ProgramError>
-    where
-        Self: Sized;
-}
+use crate::state::CounterBounds;
 
 #[derive(BorshSerialize, BorshDeserialize)]
 #[borsh(use_discriminant = true)]
 #[repr(u8)]
 pub enum CounterInstruction {
-    InitCounter = 0,
+    /// Create the counter PDA derived from the payer and `seed`, optionally kept within `bounds`
+    InitCounter {
+        initial_value: i64,
+        seed: u64,
+        bounds: Option<CounterBounds>,
+    } = 0,
     IncCounter = 1,
     DecCounter = 2,
+    SetAuthority {
+        new_authority: Pubkey,
+    } = 3,
+    IncrementBy {
+        amount: u64,
+    } = 4,
+    DecrementBy {
+        amount: u64,
+    } = 5,
+    /// Put the counter back to zero, subject to its bounds
+    Reset = 6,
+    /// Overwrite the counter with `value`, subject to its bounds
+    Set {
+        value: i64,
+    } = 7,
+    /// Close the counter and send its lamports to the destination account
+    CloseCounter = 8,
+    /// Rewrite a legacy keypair counter in the current layout, the counter keypair signs and
+    /// the new authority pays for the extra rent
+    Migrate = 9,
 }
 
-impl Unpack for CounterInstruction {
-    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        BorshDeserialize::deserialize(&mut &input[..])
-            .map_err(|_| ProgramError::InvalidInstructionData)
-    }
-}
+impl Unpack for CounterInstruction {}
@@ -1,80 +1,243 @@
-use crate::{error::CustomError, state::CounterAccount};
-use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{
-    account_info::next_account_info, account_info::AccountInfo, entrypoint::ProgramResult, msg,
-    program::invoke, program_error::ProgramError, pubkey::Pubkey, rent::Rent, system_instruction,
-    sysvar::Sysvar,
+use crate::{
+    accounts::{
+        CloseCounterAccounts, InitCounterAccounts, MigrateCounterAccounts, UpdateCounterAccounts,
+    },
+    error::CustomError,
+    events::{
+        AuthorityChanged, CounterChanged, CounterClosed, CounterCreated, CounterEvent,
+        CounterMigrated,
+    },
+    state::{
+        find_counter_address, BoundPolicy, CounterAccount, CounterBounds, LegacyCounterAccount,
+        COUNTER_SEED,
+    },
 };
+use borsh::BorshDeserialize;
+use program_common::{
+    check_key, close_account, create_account, resize_account, AccountSize, Event,
+};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Fail unless the signing `authority_account` is the authority of `counter_data`
+fn check_authority(
+    counter_data: &CounterAccount,
+    authority_account: &AccountInfo,
+) -> ProgramResult {
+    check_key(
+        authority_account,
+        &counter_data.authority,
+        CustomError::InvalidAuthority,
+    )
+}
+
+/// Turn the result of a change into the new counter value, enforcing the counter bounds
+fn settle(bounds: Option<CounterBounds>, value: i128) -> Result<i64, CustomError> {
+    match bounds {
+        None => i64::try_from(value).map_err(|_| match value > 0 {
+            true => CustomError::Overflow,
+            false => CustomError::Underflow,
+        }),
+        Some(bounds) if value > i128::from(bounds.max) => match bounds.policy {
+            BoundPolicy::Reject => Err(CustomError::AboveMaximum),
+            BoundPolicy::Saturate => Ok(bounds.max),
+        },
+        Some(bounds) if value < i128::from(bounds.min) => match bounds.policy {
+            BoundPolicy::Reject => Err(CustomError::BelowMinimum),
+            BoundPolicy::Saturate => Ok(bounds.min),
+        },
+        // within [min, max], so it fits in an i64
+        Some(_) => Ok(value as i64),
+    }
+}
 
 pub fn process_initialize_counter(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     initial_value: i64,
+    seed: u64,
+    bounds: Option<CounterBounds>,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-
-    let counter_account = next_account_info(accounts_iter)?;
-    let payer_account = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
-    let size_data = i64::BITS / 8;
-
-    let rent = Rent::get()?;
-
-    let required_lamports = rent.minimum_balance(size_data as usize);
-    invoke(
-        &system_instruction::create_account(
-            payer_account.key,   //account paying for the new account
-            counter_account.key, //account to be created
-            required_lamports,   // amount of lamport given to the new account
-            size_data.into(),    //size in bytes to allocate for the data field
-            program_id,          //program owner is set to our program
-        ),
-        &[
-            payer_account.clone(),
-            counter_account.clone(),
-            system_program.clone(),
-        ],
+    let InitCounterAccounts {
+        counter: counter_account,
+        payer: payer_account,
+        system_program,
+    } = InitCounterAccounts::try_from(accounts)?;
+
+    let (counter_key, bump) = find_counter_address(program_id, payer_account.key, seed);
+    check_key(
+        counter_account,
+        &counter_key,
+        CustomError::InvalidCounterAddress,
     )?;
 
-    let counter_data = CounterAccount {
-        count: initial_value,
-    };
+    if let Some(bounds) = bounds {
+        if bounds.min > bounds.max || !(bounds.min..=bounds.max).contains(&initial_value) {
+            return Err(CustomError::InvalidBounds.into());
+        }
+    }
+
+    let counter_data = CounterAccount::new(initial_value, *payer_account.key, bump, bounds);
 
-    let mut account_data = &mut counter_account.data.borrow_mut()[..];
-    counter_data.serialize(&mut account_data)?;
+    create_account(
+        payer_account,
+        counter_account,
+        system_program,
+        program_id,
+        counter_data.account_size(),
+        //the counter PDA signs its own creation
+        &[&[
+            COUNTER_SEED,
+            payer_account.key.as_ref(),
+            &seed.to_le_bytes(),
+            &[bump],
+        ]],
+    )?;
+
+    counter_data.pack_into(&mut counter_account.data.borrow_mut())?;
     msg!("counter init to {}", initial_value);
-    Ok(())
+    CounterEvent::CounterCreated(CounterCreated {
+        counter: *counter_account.key,
+        authority: *payer_account.key,
+        value: initial_value,
+    })
+    .emit()
 }
 
 pub fn process_change_counter(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     inc: bool,
+    amount: u64,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let counter_account = next_account_info(accounts_iter)?;
+    let UpdateCounterAccounts {
+        counter: counter_account,
+        authority: authority_account,
+    } = UpdateCounterAccounts::try_from(program_id, accounts)?;
 
-    if counter_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
+    let mut data = counter_account.data.borrow_mut();
+    let mut counter_data = CounterAccount::unpack(&data)?;
+    check_authority(&counter_data, authority_account)?;
+    // i128 holds any i64 moved by any u64, the bounds decide what to do with the result
+    let old_value = counter_data.count;
+    let value = match inc {
+        true => i128::from(old_value) + i128::from(amount),
+        false => i128::from(old_value) - i128::from(amount),
+    };
+    counter_data.count = settle(counter_data.bounds, value)?;
+    counter_data.pack_into(&mut data)?;
+    match inc {
+        true => msg!("counter incremented to: {}", counter_data.count),
+        false => msg!("counter decremented to: {}", counter_data.count),
     }
+    CounterEvent::CounterChanged(CounterChanged {
+        counter: *counter_account.key,
+        old_value,
+        new_value: counter_data.count,
+    })
+    .emit()
+}
+
+pub fn process_set_counter(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    value: i64,
+) -> ProgramResult {
+    let UpdateCounterAccounts {
+        counter: counter_account,
+        authority: authority_account,
+    } = UpdateCounterAccounts::try_from(program_id, accounts)?;
 
     let mut data = counter_account.data.borrow_mut();
-    let mut counter_data: CounterAccount = CounterAccount::try_from_slice(&data)?;
-    match inc {
-        true => {
-            counter_data.count = counter_data
-                .count
-                .checked_add(1)
-                .ok_or(CustomError::Overflow)?;
-        }
-        false => {
-            counter_data.count = counter_data
-                .count
-                .checked_sub(1)
-                .ok_or(CustomError::Underflow)?;
-        }
-    };
-    counter_data.serialize(&mut &mut data[..])?;
-    msg!("counter incremented to: {}", counter_data.count);
-    Ok(())
+    let mut counter_data = CounterAccount::unpack(&data)?;
+    check_authority(&counter_data, authority_account)?;
+
+    let old_value = counter_data.count;
+    counter_data.count = settle(counter_data.bounds, i128::from(value))?;
+    counter_data.pack_into(&mut data)?;
+    msg!("counter set from {} to {}", old_value, counter_data.count);
+    CounterEvent::CounterChanged(CounterChanged {
+        counter: *counter_account.key,
+        old_value,
+        new_value: counter_data.count,
+    })
+    .emit()
+}
+
+pub fn process_set_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_authority: Pubkey,
+) -> ProgramResult {
+    let UpdateCounterAccounts {
+        counter: counter_account,
+        authority: authority_account,
+    } = UpdateCounterAccounts::try_from(program_id, accounts)?;
+
+    let mut data = counter_account.data.borrow_mut();
+    let mut counter_data = CounterAccount::unpack(&data)?;
+    check_authority(&counter_data, authority_account)?;
+
+    let old_authority = counter_data.authority;
+    counter_data.authority = new_authority;
+    counter_data.pack_into(&mut data)?;
+    msg!("counter authority set to: {}", new_authority);
+    CounterEvent::AuthorityChanged(AuthorityChanged {
+        counter: *counter_account.key,
+        old_authority,
+        new_authority,
+    })
+    .emit()
+}
+
+pub fn process_close_counter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let CloseCounterAccounts {
+        counter: counter_account,
+        authority: authority_account,
+        destination: dest_account,
+    } = CloseCounterAccounts::try_from(program_id, accounts)?;
+
+    let counter_data = CounterAccount::unpack(&counter_account.data.borrow())?;
+    check_authority(&counter_data, authority_account)?;
+
+    let lamports = counter_account.lamports();
+    close_account(counter_account, dest_account)?;
+    msg!("counter {} closed", counter_account.key);
+    CounterEvent::CounterClosed(CounterClosed {
+        counter: *counter_account.key,
+        destination: *dest_account.key,
+        lamports,
+    })
+    .emit()
+}
+
+pub fn process_migrate_counter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let MigrateCounterAccounts {
+        counter: counter_account,
+        authority: authority_account,
+        system_program,
+    } = MigrateCounterAccounts::try_from(program_id, accounts)?;
+    if counter_account.data_len() != LegacyCounterAccount::LEN {
+        //anything else must already be a current counter
+        CounterAccount::unpack(&counter_account.data.borrow())?;
+        return Err(CustomError::AlreadyMigrated.into());
+    }
+
+    let legacy_data = LegacyCounterAccount::try_from_slice(&counter_account.data.borrow())?;
+    let counter_data = CounterAccount::new(legacy_data.count, *authority_account.key, 0, None);
+
+    resize_account(
+        counter_account,
+        authority_account,
+        system_program,
+        counter_data.account_size(),
+    )?;
+
+    counter_data.pack_into(&mut counter_account.data.borrow_mut())?;
+    msg!("counter migrated with value {}", counter_data.count);
+    CounterEvent::CounterMigrated(CounterMigrated {
+        counter: *counter_account.key,
+        authority: *authority_account.key,
+        value: counter_data.count,
+    })
+    .emit()
 }
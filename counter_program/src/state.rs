@@ -1,6 +1,96 @@
+use crate::error::CustomError;
 use borsh::{BorshDeserialize, BorshSerialize};
+use program_common::{check_header, AccountSize, ACCOUNT_HEADER_LEN};
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
 
-#[derive(BorshSerialize, BorshDeserialize)]
+/// Seed prefix of the counter PDA, derived from [COUNTER_SEED, authority, seed]
+pub const COUNTER_SEED: &[u8] = b"counter";
+/// Layout version written by this program in every counter it creates
+pub const ACCOUNT_VERSION: u8 = 1;
+
+/// What happens to a change that would leave the counter bounds
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[borsh(use_discriminant = true)]
+#[repr(u8)]
+pub enum BoundPolicy {
+    /// Fail the instruction
+    Reject = 0,
+    /// Clamp the counter to the bound it crossed
+    Saturate = 1,
+}
+
+/// Inclusive range a counter is allowed to hold
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CounterBounds {
+    pub min: i64,
+    pub max: i64,
+    pub policy: BoundPolicy,
+}
+
+impl CounterBounds {
+    pub const LEN: usize = 8 + 8 + 1;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct CounterAccount {
+    /// Always [CounterAccount::DISCRIMINATOR]
+    pub discriminator: [u8; 8],
+    /// Layout version of the account
+    pub version: u8,
+    pub count: i64,
+    /// Only this key can change the counter or hand it over to someone else
+    pub authority: Pubkey,
+    /// Bump seed of the counter PDA, 0 for counters migrated from a legacy keypair account
+    pub bump: u8,
+    /// Range enforced on every change, the whole i64 range when unset
+    pub bounds: Option<CounterBounds>,
+}
+
+impl CounterAccount {
+    pub const DISCRIMINATOR: [u8; 8] = *b"counter\0";
+
+    pub fn new(count: i64, authority: Pubkey, bump: u8, bounds: Option<CounterBounds>) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            count,
+            authority,
+            bump,
+            bounds,
+        }
+    }
+
+    /// Deserialize a counter, refusing any other account type or layout version
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        check_header(data, &Self::DISCRIMINATOR, ACCOUNT_VERSION).map_err(CustomError::from)?;
+        Ok(Self::try_from_slice(data)?)
+    }
+}
+
+impl AccountSize for CounterAccount {
+    fn account_size(&self) -> usize {
+        let bounds_size = self.bounds.map_or(0, |_| CounterBounds::LEN);
+        ACCOUNT_HEADER_LEN + 8 + PUBKEY_BYTES + 1 + 1 + bounds_size
+    }
+}
+
+/// Layout of the counters created before accounts carried a discriminator, only read by Migrate
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LegacyCounterAccount {
     pub count: i64,
 }
+
+impl LegacyCounterAccount {
+    pub const LEN: usize = 8;
+}
+
+/// Address of the counter created by `authority` with `seed`, along with its bump seed
+pub fn find_counter_address(program_id: &Pubkey, authority: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COUNTER_SEED, authority.as_ref(), &seed.to_le_bytes()],
+        program_id,
+    )
+}
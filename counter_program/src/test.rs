@@ -1,15 +1,24 @@
 use borsh::BorshDeserialize;
-use env_logger;
+use error::CustomError;
+use events::{AuthorityChanged, CounterChanged, CounterClosed, CounterCreated, CounterEvent};
 use log::{error, info};
+use program_common::{stubs, AccountSize, ErrorCode, Event};
 use solana_program_test::*;
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
+    account::Account,
+    hash::Hash,
+    instruction::{AccountMeta, InstructionError},
+    program_error::ProgramError,
+    rent::Rent,
     signature::Keypair,
     signer::Signer,
-    system_program,
-    transaction::Transaction,
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use state::{
+    find_counter_address, BoundPolicy, CounterAccount, CounterBounds, LegacyCounterAccount,
+    ACCOUNT_VERSION,
 };
-use state::CounterAccount;
 
 use super::*;
 
@@ -19,7 +28,7 @@ fn panic_log(content: String) -> ! {
 }
 
 fn setup() {
-    env_logger::builder()
+    let _ = env_logger::builder()
         .is_test(true)
         //disable all logs by defaykt
         .filter(None, log::LevelFilter::Off)
@@ -30,7 +39,9 @@ fn setup() {
         )
         //enable our test log
         .filter_module("counter_program::test", log::LevelFilter::Trace)
-        .init();
+        .try_init();
+
+    stubs::log_data_to_transaction_logs();
 }
 
 #[tokio::test]
@@ -43,27 +54,16 @@ async fn test_init() {
             .start()
             .await;
 
-    let counter_keypair = Keypair::new();
     let init_val = 0i64;
-
-    let mut init_instruction_data: Vec<u8> = vec![CounterInstruction::InitCounter as u8];
-    init_instruction_data.extend_from_slice(&init_val.to_le_bytes());
-
-    let init_instruction = Instruction::new_with_bytes(
-        program_id,
-        &init_instruction_data,
-        vec![
-            AccountMeta::new(counter_keypair.pubkey(), true),
-            AccountMeta::new(payer.pubkey(), true),
-            AccountMeta::new_readonly(system_program::id(), false),
-        ],
-    );
+    let (counter_key, _) = find_counter_address(&program_id, &payer.pubkey(), 0);
+    let init_instruction =
+        instruction::init_counter(&program_id, &payer.pubkey(), 0, init_val, None);
 
     let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
-    tx.sign(&[&payer, &counter_keypair], recent_blockhash);
+    tx.sign(&[&payer], recent_blockhash);
     bank_clients.process_transaction(tx).await.unwrap();
 
-    let account = match bank_clients.get_account(counter_keypair.pubkey()).await {
+    let account = match bank_clients.get_account(counter_key).await {
         Ok(x) => x,
         Err(err) => {
             panic_log(format!("failed to get counter account: {}", err));
@@ -74,6 +74,7 @@ async fn test_init() {
         let counter = CounterAccount::try_from_slice(&account_data.data)
             .expect("failed to deserialize counter data");
         assert_eq!(counter.count, init_val);
+        assert_eq!(counter.authority, payer.pubkey());
         info!("counter init successfully with value {}", counter.count);
     } else {
         panic_log("No counter account found".to_string());
@@ -81,17 +82,13 @@ async fn test_init() {
 
     info!("Testing counter increment...");
 
-    let inc_instructionb = Instruction::new_with_bytes(
-        program_id,
-        &[CounterInstruction::IncCounter as u8],
-        vec![AccountMeta::new(counter_keypair.pubkey(), true)],
-    );
+    let inc_instructionb = instruction::increment(&program_id, &counter_key, &payer.pubkey());
 
     let mut tx = Transaction::new_with_payer(&[inc_instructionb], Some(&payer.pubkey()));
-    tx.sign(&[&payer, &counter_keypair], recent_blockhash);
+    tx.sign(&[&payer], recent_blockhash);
     bank_clients.process_transaction(tx).await.unwrap();
 
-    let account = match bank_clients.get_account(counter_keypair.pubkey()).await {
+    let account = match bank_clients.get_account(counter_key).await {
         Ok(x) => x,
         Err(err) => {
             panic_log(format!("failed to get counter account: {}", err));
@@ -104,22 +101,18 @@ async fn test_init() {
         assert_eq!(counter.count, init_val + 1);
         info!("counter incremented successfully to {}", counter.count);
     } else {
-        panic_log(format!("No counter account found"));
+        panic_log("No counter account found".to_string());
     }
 
     info!("Testing counter decrement...");
 
-    let dec_instruction = Instruction::new_with_bytes(
-        program_id,
-        &[CounterInstruction::DecCounter as u8],
-        vec![AccountMeta::new(counter_keypair.pubkey(), true)],
-    );
+    let dec_instruction = instruction::decrement(&program_id, &counter_key, &payer.pubkey());
 
     let mut tx = Transaction::new_with_payer(&[dec_instruction], Some(&payer.pubkey()));
-    tx.sign(&[&payer, &counter_keypair], recent_blockhash);
+    tx.sign(&[&payer], recent_blockhash);
     bank_clients.process_transaction(tx).await.unwrap();
 
-    let account = match bank_clients.get_account(counter_keypair.pubkey()).await {
+    let account = match bank_clients.get_account(counter_key).await {
         Ok(x) => x,
         Err(err) => {
             panic_log(format!("failed to get counter account: {}", err));
@@ -132,6 +125,1051 @@ async fn test_init() {
         assert_eq!(counter.count, init_val);
         info!("counter decremented successfully to {}", counter.count);
     } else {
-        panic_log(format!("No counter account found"));
+        panic_log("No counter account found".to_string());
+    }
+}
+
+async fn init_counter_with_value(
+    initial_value: i64,
+) -> (BanksClient, Keypair, Hash, Pubkey, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("counter_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    let (counter_key, _) = find_counter_address(&program_id, &payer.pubkey(), 0);
+    let init_instruction =
+        instruction::init_counter(&program_id, &payer.pubkey(), 0, initial_value, None);
+
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    (
+        bank_clients,
+        payer,
+        recent_blockhash,
+        program_id,
+        counter_key,
+    )
+}
+
+async fn get_counter(bank_clients: &mut BanksClient, counter: Pubkey) -> CounterAccount {
+    let account = bank_clients
+        .get_account(counter)
+        .await
+        .unwrap()
+        .expect("No counter account found");
+    CounterAccount::try_from_slice(&account.data).expect("failed to deserialize counter data")
+}
+
+#[tokio::test]
+async fn test_init_with_positive_value() {
+    setup();
+
+    let init_val = 1_337i64;
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(init_val).await;
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, init_val);
+    info!("counter init successfully with value {}", counter.count);
+
+    let inc_instruction = instruction::increment(&program_id, &counter_key, &payer.pubkey());
+    let mut tx = Transaction::new_with_payer(&[inc_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, init_val + 1);
+}
+
+#[tokio::test]
+async fn test_init_with_negative_value() {
+    setup();
+
+    let init_val = -4_242i64;
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(init_val).await;
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, init_val);
+    info!("counter init successfully with value {}", counter.count);
+
+    let dec_instruction = instruction::decrement(&program_id, &counter_key, &payer.pubkey());
+    let mut tx = Transaction::new_with_payer(&[dec_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, init_val - 1);
+}
+
+#[tokio::test]
+async fn test_init_at_limits() {
+    setup();
+
+    for (init_val, inc, error) in [
+        (i64::MAX, true, CustomError::Overflow),
+        (i64::MIN, false, CustomError::Underflow),
+    ] {
+        let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+            init_counter_with_value(init_val).await;
+
+        let counter = get_counter(&mut bank_clients, counter_key).await;
+        assert_eq!(counter.count, init_val);
+
+        let change_instruction = match inc {
+            true => instruction::increment(&program_id, &counter_key, &payer.pubkey()),
+            false => instruction::decrement(&program_id, &counter_key, &payer.pubkey()),
+        };
+        let mut tx = Transaction::new_with_payer(&[change_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        let err = bank_clients.process_transaction(tx).await.unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+
+        let counter = get_counter(&mut bank_clients, counter_key).await;
+        assert_eq!(counter.count, init_val);
+    }
+}
+
+#[tokio::test]
+async fn test_set_authority() {
+    setup();
+
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(0).await;
+    let new_authority = Keypair::new();
+
+    let set_authority_instruction = instruction::set_authority(
+        &program_id,
+        &counter_key,
+        &payer.pubkey(),
+        &new_authority.pubkey(),
+    );
+    let mut tx = Transaction::new_with_payer(&[set_authority_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.authority, new_authority.pubkey());
+    info!("counter authority set to {}", counter.authority);
+
+    // the previous authority can no longer change the counter
+    let inc_instruction = instruction::increment(&program_id, &counter_key, &payer.pubkey());
+    let mut tx = Transaction::new_with_payer(&[inc_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::InvalidAuthority as u32)
+        )
+    );
+
+    // the new authority can
+    let inc_instruction =
+        instruction::increment(&program_id, &counter_key, &new_authority.pubkey());
+    let mut tx = Transaction::new_with_payer(&[inc_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &new_authority], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, 1);
+}
+
+#[tokio::test]
+async fn test_change_without_authority_signature() {
+    setup();
+
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(0).await;
+
+    for mut change_instruction in [
+        instruction::increment(&program_id, &counter_key, &payer.pubkey()),
+        instruction::decrement(&program_id, &counter_key, &payer.pubkey()),
+        instruction::set_authority(
+            &program_id,
+            &counter_key,
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+        ),
+    ] {
+        // the authority is listed but has not signed, a third party pays the fees
+        let fee_payer = Keypair::new();
+        let fund_instruction =
+            system_instruction::transfer(&payer.pubkey(), &fee_payer.pubkey(), 1_000_000_000);
+        let mut tx = Transaction::new_with_payer(&[fund_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        change_instruction.accounts[1].is_signer = false;
+        let mut tx = Transaction::new_with_payer(&[change_instruction], Some(&fee_payer.pubkey()));
+        tx.sign(&[&fee_payer], recent_blockhash);
+        let err = bank_clients.process_transaction(tx).await.unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(CustomError::MissingAuthoritySignature as u32)
+            )
+        );
+    }
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, 0);
+    assert_eq!(counter.authority, payer.pubkey());
+}
+
+#[tokio::test]
+async fn test_counter_address_derivation() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("counter_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    // one authority can own several counters, one per seed
+    for seed in [0u64, 1, u64::MAX] {
+        let (counter_key, bump) = find_counter_address(&program_id, &payer.pubkey(), seed);
+        let init_instruction =
+            instruction::init_counter(&program_id, &payer.pubkey(), seed, seed as i64, None);
+        let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        let counter = get_counter(&mut bank_clients, counter_key).await;
+        assert_eq!(counter.count, seed as i64);
+        assert_eq!(counter.authority, payer.pubkey());
+        assert_eq!(counter.bump, bump);
+        info!("counter {} created for seed {}", counter_key, seed);
+    }
+
+    // any other address is refused, even when it signs
+    let counter_keypair = Keypair::new();
+    let mut init_instruction = instruction::init_counter(&program_id, &payer.pubkey(), 2, 0, None);
+    init_instruction.accounts[0] = AccountMeta::new(counter_keypair.pubkey(), true);
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &counter_keypair], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::InvalidCounterAddress as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_init_prefunded_counter() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("counter_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+    let rent = bank_clients.get_rent().await.unwrap();
+
+    // anyone can derive a counter address and send it lamports before it is initialized, with
+    // less than the rent of a counter or more
+    for (seed, prefund) in [(0u64, rent.minimum_balance(0)), (1, 1_000_000_000)] {
+        let (counter_key, _) = find_counter_address(&program_id, &payer.pubkey(), seed);
+        let mut tx = Transaction::new_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &counter_key,
+                prefund,
+            )],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        let init_instruction =
+            instruction::init_counter(&program_id, &payer.pubkey(), seed, 7, None);
+        let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        let account = bank_clients
+            .get_account(counter_key)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.owner, program_id);
+        assert_eq!(
+            account.lamports,
+            prefund.max(rent.minimum_balance(account.data.len()))
+        );
+        let counter = get_counter(&mut bank_clients, counter_key).await;
+        assert_eq!(counter.count, 7);
+        assert_eq!(counter.authority, payer.pubkey());
+        info!("counter {} created over {} lamports", counter_key, prefund);
+    }
+}
+
+#[tokio::test]
+async fn test_change_by_amount() {
+    setup();
+
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(0).await;
+
+    // a whole batch of events fits in one transaction
+    let batch = [
+        instruction::increment_by(&program_id, &counter_key, &payer.pubkey(), 500),
+        instruction::decrement_by(&program_id, &counter_key, &payer.pubkey(), 42),
+        instruction::increment_by(&program_id, &counter_key, &payer.pubkey(), 0),
+        instruction::decrement_by(&program_id, &counter_key, &payer.pubkey(), 1_000),
+    ];
+    let mut tx = Transaction::new_with_payer(&batch, Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, -542);
+    info!("counter moved by batch to {}", counter.count);
+}
+
+#[tokio::test]
+async fn test_change_by_amount_at_limits() {
+    setup();
+
+    // amounts above i64::MAX are valid as long as the result fits
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(i64::MIN).await;
+    let inc_instruction =
+        instruction::increment_by(&program_id, &counter_key, &payer.pubkey(), u64::MAX);
+    let mut tx = Transaction::new_with_payer(&[inc_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, i64::MAX);
+
+    let dec_instruction =
+        instruction::decrement_by(&program_id, &counter_key, &payer.pubkey(), u64::MAX);
+    let mut tx = Transaction::new_with_payer(&[dec_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, i64::MIN);
+
+    for (inc, amount, error) in [
+        (false, 1, CustomError::Underflow),
+        (true, u64::MAX, CustomError::Overflow),
+    ] {
+        let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+            init_counter_with_value(match inc {
+                true => 1,
+                false => i64::MIN,
+            })
+            .await;
+        let change_instruction = match inc {
+            true => instruction::increment_by(&program_id, &counter_key, &payer.pubkey(), amount),
+            false => instruction::decrement_by(&program_id, &counter_key, &payer.pubkey(), amount),
+        };
+        let mut tx = Transaction::new_with_payer(&[change_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        let err = bank_clients.process_transaction(tx).await.unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_bounds() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("counter_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    // empty ranges and initial values outside the range are refused
+    for (seed, initial_value, min, max) in
+        [(0u64, 0i64, 1i64, -1i64), (1, 11, 0, 10), (2, -1, 0, 10)]
+    {
+        let bounds = CounterBounds {
+            min,
+            max,
+            policy: BoundPolicy::Reject,
+        };
+        let init_instruction = instruction::init_counter(
+            &program_id,
+            &payer.pubkey(),
+            seed,
+            initial_value,
+            Some(bounds),
+        );
+        let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        let err = bank_clients.process_transaction(tx).await.unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(CustomError::InvalidBounds as u32)
+            )
+        );
     }
+
+    for (seed, policy) in [(3u64, BoundPolicy::Reject), (4, BoundPolicy::Saturate)] {
+        let bounds = CounterBounds {
+            min: 0,
+            max: 10,
+            policy,
+        };
+        let (counter_key, _) = find_counter_address(&program_id, &payer.pubkey(), seed);
+        let init_instruction =
+            instruction::init_counter(&program_id, &payer.pubkey(), seed, 5, Some(bounds));
+        let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        let counter = get_counter(&mut bank_clients, counter_key).await;
+        assert_eq!(counter.bounds, Some(bounds));
+
+        // landing exactly on a bound is always allowed
+        let inc_instruction =
+            instruction::increment_by(&program_id, &counter_key, &payer.pubkey(), 5);
+        let mut tx = Transaction::new_with_payer(&[inc_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+        assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 10);
+
+        for (inc, bound, error) in [
+            (true, 10, CustomError::AboveMaximum),
+            (false, 0, CustomError::BelowMinimum),
+        ] {
+            let change_instruction = match inc {
+                true => {
+                    instruction::increment_by(&program_id, &counter_key, &payer.pubkey(), u64::MAX)
+                }
+                false => {
+                    instruction::decrement_by(&program_id, &counter_key, &payer.pubkey(), u64::MAX)
+                }
+            };
+            let mut tx = Transaction::new_with_payer(&[change_instruction], Some(&payer.pubkey()));
+            tx.sign(&[&payer], recent_blockhash);
+            let result = bank_clients.process_transaction(tx).await;
+            match policy {
+                BoundPolicy::Reject => assert_eq!(
+                    result.unwrap_err().unwrap(),
+                    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+                ),
+                BoundPolicy::Saturate => {
+                    result.unwrap();
+                    assert_eq!(
+                        get_counter(&mut bank_clients, counter_key).await.count,
+                        bound
+                    );
+                }
+            }
+            info!("counter {:?} handled a change past {}", policy, bound);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_set_and_reset() {
+    setup();
+
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(42).await;
+
+    let set_instruction = instruction::set(&program_id, &counter_key, &payer.pubkey(), i64::MIN);
+    let mut tx = Transaction::new_with_payer(&[set_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+    assert_eq!(
+        get_counter(&mut bank_clients, counter_key).await.count,
+        i64::MIN
+    );
+
+    let reset_instruction = instruction::reset(&program_id, &counter_key, &payer.pubkey());
+    let mut tx = Transaction::new_with_payer(&[reset_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+    assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 0);
+
+    // only the authority can overwrite the counter
+    let intruder = Keypair::new();
+    for mut change_instruction in [
+        instruction::set(&program_id, &counter_key, &payer.pubkey(), 7),
+        instruction::reset(&program_id, &counter_key, &payer.pubkey()),
+    ] {
+        change_instruction.accounts[1] = AccountMeta::new_readonly(intruder.pubkey(), true);
+        let mut tx = Transaction::new_with_payer(&[change_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &intruder], recent_blockhash);
+        let err = bank_clients.process_transaction(tx).await.unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(CustomError::InvalidAuthority as u32)
+            )
+        );
+    }
+    assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 0);
+}
+
+#[tokio::test]
+async fn test_set_with_bounds() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("counter_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    for (seed, policy) in [(0u64, BoundPolicy::Reject), (1, BoundPolicy::Saturate)] {
+        let bounds = CounterBounds {
+            min: 5,
+            max: 10,
+            policy,
+        };
+        let (counter_key, _) = find_counter_address(&program_id, &payer.pubkey(), seed);
+        let init_instruction =
+            instruction::init_counter(&program_id, &payer.pubkey(), seed, 7, Some(bounds));
+        let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        let set_instruction = instruction::set(&program_id, &counter_key, &payer.pubkey(), 9);
+        let mut tx = Transaction::new_with_payer(&[set_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+        assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 9);
+
+        // zero is below the minimum, so a reset follows the bound policy
+        let reset_instruction = instruction::reset(&program_id, &counter_key, &payer.pubkey());
+        let mut tx = Transaction::new_with_payer(&[reset_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        let result = bank_clients.process_transaction(tx).await;
+
+        let set_instruction = instruction::set(&program_id, &counter_key, &payer.pubkey(), 11);
+        let mut tx = Transaction::new_with_payer(&[set_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        let set_result = bank_clients.process_transaction(tx).await;
+
+        match policy {
+            BoundPolicy::Reject => {
+                assert_eq!(
+                    result.unwrap_err().unwrap(),
+                    TransactionError::InstructionError(
+                        0,
+                        InstructionError::Custom(CustomError::BelowMinimum as u32)
+                    )
+                );
+                assert_eq!(
+                    set_result.unwrap_err().unwrap(),
+                    TransactionError::InstructionError(
+                        0,
+                        InstructionError::Custom(CustomError::AboveMaximum as u32)
+                    )
+                );
+                assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 9);
+            }
+            BoundPolicy::Saturate => {
+                result.unwrap();
+                set_result.unwrap();
+                assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 10);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_close_counter() {
+    setup();
+
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(3).await;
+    let destination = Keypair::new();
+    let counter_lamports = bank_clients
+        .get_account(counter_key)
+        .await
+        .unwrap()
+        .expect("No counter account found")
+        .lamports;
+
+    // only the authority can close the counter
+    let intruder = Keypair::new();
+    let mut close_instruction = instruction::close_counter(
+        &program_id,
+        &counter_key,
+        &payer.pubkey(),
+        &intruder.pubkey(),
+    );
+    close_instruction.accounts[1] = AccountMeta::new_readonly(intruder.pubkey(), true);
+    let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &intruder], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::InvalidAuthority as u32)
+        )
+    );
+
+    // refunding the closed account in the same transaction doesn't bring the counter back
+    let close_instruction = instruction::close_counter(
+        &program_id,
+        &counter_key,
+        &payer.pubkey(),
+        &destination.pubkey(),
+    );
+    let revive_instructions = [
+        close_instruction.clone(),
+        system_instruction::transfer(&payer.pubkey(), &counter_key, counter_lamports),
+        instruction::increment(&program_id, &counter_key, &payer.pubkey()),
+    ];
+    let mut tx = Transaction::new_with_payer(&revive_instructions, Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(2, InstructionError::IncorrectProgramId)
+    );
+    assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 3);
+
+    let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    assert!(bank_clients
+        .get_account(counter_key)
+        .await
+        .unwrap()
+        .is_none());
+    let destination_account = bank_clients
+        .get_account(destination.pubkey())
+        .await
+        .unwrap()
+        .expect("No destination account found");
+    assert_eq!(destination_account.lamports, counter_lamports);
+    info!("counter closed, {} lamports reclaimed", counter_lamports);
+}
+
+#[tokio::test]
+async fn test_close_counter_accounts() {
+    setup();
+
+    let (mut bank_clients, payer, recent_blockhash, program_id, counter_key) =
+        init_counter_with_value(3).await;
+
+    // the counter can't be its own destination
+    let close_instruction =
+        instruction::close_counter(&program_id, &counter_key, &payer.pubkey(), &counter_key);
+    let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::DuplicateAccount as u32)
+        )
+    );
+
+    // the counter must be passed as writable
+    let destination = Keypair::new();
+    let mut close_instruction = instruction::close_counter(
+        &program_id,
+        &counter_key,
+        &payer.pubkey(),
+        &destination.pubkey(),
+    );
+    close_instruction.accounts[0] = AccountMeta::new_readonly(counter_key, false);
+    let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::AccountNotWritable as u32)
+        )
+    );
+    assert_eq!(get_counter(&mut bank_clients, counter_key).await.count, 3);
+}
+
+#[test]
+fn test_error_codes() {
+    // the codes are part of the program interface, clients match on them
+    let expected = [
+        (CustomError::Overflow, 0),
+        (CustomError::Underflow, 1),
+        (CustomError::MissingAuthoritySignature, 2),
+        (CustomError::InvalidAuthority, 3),
+        (CustomError::InvalidCounterAddress, 4),
+        (CustomError::InvalidBounds, 5),
+        (CustomError::AboveMaximum, 6),
+        (CustomError::BelowMinimum, 7),
+        (CustomError::InvalidAccountDiscriminator, 8),
+        (CustomError::UnsupportedAccountVersion, 9),
+        (CustomError::AlreadyMigrated, 10),
+        (CustomError::AccountNotWritable, 11),
+        (CustomError::DuplicateAccount, 12),
+    ];
+    assert_eq!(CustomError::ALL.len(), expected.len());
+    for (error, code) in expected {
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        assert_eq!(CustomError::from_code(code), Some(error));
+    }
+    assert_eq!(CustomError::from_code(expected.len() as u32), None);
+}
+
+#[test]
+fn test_account_size() {
+    // sizes drive account allocation, they must match the serialized layout byte for byte
+    for bounds in [
+        None,
+        Some(CounterBounds {
+            min: i64::MIN,
+            max: i64::MAX,
+            policy: BoundPolicy::Saturate,
+        }),
+    ] {
+        if let Some(bounds) = &bounds {
+            assert_eq!(CounterBounds::LEN, borsh::to_vec(bounds).unwrap().len());
+        }
+        let counter_data = CounterAccount::new(-1, Pubkey::new_unique(), 255, bounds);
+        assert_eq!(
+            counter_data.account_size(),
+            borsh::to_vec(&counter_data).unwrap().len()
+        );
+    }
+
+    let legacy_data = LegacyCounterAccount { count: i64::MAX };
+    assert_eq!(
+        LegacyCounterAccount::LEN,
+        borsh::to_vec(&legacy_data).unwrap().len()
+    );
+
+    // packing refuses an account whose size doesn't match the state
+    let counter_data = CounterAccount::new(7, Pubkey::new_unique(), 255, None);
+    let mut data = vec![0; counter_data.account_size() + 1];
+    assert_eq!(
+        counter_data.pack_into(&mut data),
+        Err(ProgramError::InvalidAccountData)
+    );
+    counter_data.pack_into(&mut data[1..]).unwrap();
+    assert_eq!(CounterAccount::unpack(&data[1..]).unwrap().count, 7);
+}
+
+#[tokio::test]
+async fn test_account_discriminator() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("counter_program", program_id, processor!(entrypoints));
+
+    // a bare i64 owned by the program used to pass for a counter
+    let untagged_key = Pubkey::new_unique();
+    program_test.add_account(
+        untagged_key,
+        Account {
+            lamports: 1_000_000_000,
+            data: 42i64.to_le_bytes().to_vec(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    // a counter written with a layout this program doesn't know
+    let future_key = Pubkey::new_unique();
+    let mut future_counter = CounterAccount::new(42, Pubkey::new_unique(), 255, None);
+    future_counter.version = ACCOUNT_VERSION + 1;
+    program_test.add_account(
+        future_key,
+        Account {
+            lamports: 1_000_000_000,
+            data: borsh::to_vec(&future_counter).unwrap(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let (mut bank_clients, payer, recent_blockhash) = program_test.start().await;
+
+    for (counter_key, error) in [
+        (untagged_key, CustomError::InvalidAccountDiscriminator),
+        (future_key, CustomError::UnsupportedAccountVersion),
+    ] {
+        let inc_instruction = instruction::increment(&program_id, &counter_key, &payer.pubkey());
+        let mut tx = Transaction::new_with_payer(&[inc_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        let err = bank_clients.process_transaction(tx).await.unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_counter() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("counter_program", program_id, processor!(entrypoints));
+
+    // raw bytes of a counter created by the first version of the program, a bare i64 in a
+    // keypair account
+    let legacy_counter = Keypair::new();
+    program_test.add_account(
+        legacy_counter.pubkey(),
+        Account {
+            lamports: Rent::default().minimum_balance(LegacyCounterAccount::LEN),
+            data: 42i64.to_le_bytes().to_vec(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let (mut bank_clients, payer, recent_blockhash) = program_test.start().await;
+
+    // without the counter keypair anyone could claim the counter
+    let mut migrate_instruction =
+        instruction::migrate_counter(&program_id, &legacy_counter.pubkey(), &payer.pubkey());
+    migrate_instruction.accounts[0] = AccountMeta::new(legacy_counter.pubkey(), false);
+    let mut tx = Transaction::new_with_payer(&[migrate_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::MissingAuthoritySignature as u32)
+        )
+    );
+
+    let migrate_instruction =
+        instruction::migrate_counter(&program_id, &legacy_counter.pubkey(), &payer.pubkey());
+    let mut tx = Transaction::new_with_payer(
+        &[
+            migrate_instruction.clone(),
+            instruction::increment(&program_id, &legacy_counter.pubkey(), &payer.pubkey()),
+        ],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer, &legacy_counter], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let account = bank_clients
+        .get_account(legacy_counter.pubkey())
+        .await
+        .unwrap()
+        .expect("No counter account found");
+    let rent = bank_clients.get_rent().await.unwrap();
+    assert!(rent.is_exempt(account.lamports, account.data.len()));
+    let counter = CounterAccount::unpack(&account.data).unwrap();
+    assert_eq!(counter.count, 43);
+    assert_eq!(counter.authority, payer.pubkey());
+    assert_eq!(counter.bounds, None);
+    info!("legacy counter migrated to {}", counter.count);
+
+    let mut tx = Transaction::new_with_payer(&[migrate_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &legacy_counter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::AlreadyMigrated as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_accounts() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("counter_program", program_id, processor!(entrypoints));
+    // the authority is not the fee payer, the runtime would make it writable otherwise
+    let authority = Keypair::new();
+    program_test.add_account(
+        authority.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::ID),
+    );
+    let legacy_counter = Keypair::new();
+    program_test.add_account(
+        legacy_counter.pubkey(),
+        Account {
+            lamports: Rent::default().minimum_balance(LegacyCounterAccount::LEN),
+            data: 7i64.to_le_bytes().to_vec(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let (mut bank_clients, payer, recent_blockhash) = program_test.start().await;
+
+    let (counter_key, _) = find_counter_address(&program_id, &authority.pubkey(), 0);
+    let init_instruction = instruction::init_counter(&program_id, &authority.pubkey(), 0, 3, None);
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &authority], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let authority_key = authority.pubkey();
+    let instructions = [
+        instruction::init_counter(&program_id, &authority_key, 1, 0, None),
+        instruction::increment(&program_id, &counter_key, &authority_key),
+        instruction::decrement(&program_id, &counter_key, &authority_key),
+        instruction::increment_by(&program_id, &counter_key, &authority_key, 2),
+        instruction::decrement_by(&program_id, &counter_key, &authority_key, 2),
+        instruction::reset(&program_id, &counter_key, &authority_key),
+        instruction::set(&program_id, &counter_key, &authority_key, 5),
+        instruction::set_authority(
+            &program_id,
+            &counter_key,
+            &authority_key,
+            &Pubkey::new_unique(),
+        ),
+        instruction::close_counter(
+            &program_id,
+            &counter_key,
+            &authority_key,
+            &Pubkey::new_unique(),
+        ),
+        instruction::migrate_counter(&program_id, &legacy_counter.pubkey(), &authority_key),
+    ];
+    for instruction in &instructions {
+        for (broken, expected) in
+            stubs::broken_accounts(instruction, CustomError::AccountNotWritable)
+        {
+            let signers: Vec<&Keypair> = [&authority, &legacy_counter]
+                .into_iter()
+                .filter(|signer| {
+                    broken
+                        .accounts
+                        .iter()
+                        .any(|meta| meta.is_signer && meta.pubkey == signer.pubkey())
+                })
+                .collect();
+            let mut tx =
+                Transaction::new_with_payer(std::slice::from_ref(&broken), Some(&payer.pubkey()));
+            tx.sign(&[&[&payer][..], &signers].concat(), recent_blockhash);
+            let err = bank_clients.process_transaction(tx).await.unwrap_err();
+            assert_eq!(
+                err.unwrap(),
+                TransactionError::InstructionError(0, expected),
+                "{:?}",
+                broken
+            );
+        }
+    }
+
+    // nothing went through
+    let counter = get_counter(&mut bank_clients, counter_key).await;
+    assert_eq!(counter.count, 3);
+    assert_eq!(counter.authority, authority_key);
+    let account = bank_clients
+        .get_account(legacy_counter.pubkey())
+        .await
+        .unwrap()
+        .expect("No legacy counter account found");
+    assert_eq!(account.data, 7i64.to_le_bytes());
+    info!("every broken account list was rejected");
+}
+
+#[tokio::test]
+async fn test_events() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("counter_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+    let (counter_key, bump) = find_counter_address(&program_id, &payer.pubkey(), 0);
+    let new_authority = Keypair::new();
+    let destination = Pubkey::new_unique();
+
+    let instructions = [
+        instruction::init_counter(&program_id, &payer.pubkey(), 0, 5, None),
+        instruction::increment(&program_id, &counter_key, &payer.pubkey()),
+        instruction::decrement_by(&program_id, &counter_key, &payer.pubkey(), 3),
+        instruction::set(&program_id, &counter_key, &payer.pubkey(), 10),
+        instruction::set_authority(
+            &program_id,
+            &counter_key,
+            &payer.pubkey(),
+            &new_authority.pubkey(),
+        ),
+        instruction::close_counter(
+            &program_id,
+            &counter_key,
+            &new_authority.pubkey(),
+            &destination,
+        ),
+    ];
+    let mut tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    tx.sign(&[&payer, &new_authority], recent_blockhash);
+    let result = bank_clients
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+    let logs = stubs::runtime_logs(
+        &result
+            .metadata
+            .expect("No transaction metadata")
+            .log_messages,
+    );
+    assert!(logs
+        .iter()
+        .any(|line| line == "Program log: counter decremented to: 3"));
+
+    let rent = bank_clients.get_rent().await.unwrap();
+    let counter_lamports =
+        rent.minimum_balance(CounterAccount::new(0, payer.pubkey(), bump, None).account_size());
+    let changed = |old_value, new_value| {
+        CounterEvent::CounterChanged(CounterChanged {
+            counter: counter_key,
+            old_value,
+            new_value,
+        })
+    };
+    assert_eq!(
+        CounterEvent::decode_logs(&program_id, &logs),
+        vec![
+            CounterEvent::CounterCreated(CounterCreated {
+                counter: counter_key,
+                authority: payer.pubkey(),
+                value: 5,
+            }),
+            changed(5, 6),
+            changed(6, 3),
+            changed(3, 10),
+            CounterEvent::AuthorityChanged(AuthorityChanged {
+                counter: counter_key,
+                old_authority: payer.pubkey(),
+                new_authority: new_authority.pubkey(),
+            }),
+            CounterEvent::CounterClosed(CounterClosed {
+                counter: counter_key,
+                destination,
+                lamports: counter_lamports,
+            }),
+        ]
+    );
+    // events of another program id are not ours
+    assert!(CounterEvent::decode_logs(&Pubkey::new_unique(), &logs).is_empty());
 }
@@ -0,0 +1,148 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+
+/// Fail with `IncorrectProgramId` unless `account` is owned by `owner`
+pub fn check_owner(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner != owner {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Fail with `error` unless `account` signed the transaction
+pub fn check_signer(account: &AccountInfo, error: impl Into<ProgramError>) -> ProgramResult {
+    if !account.is_signer {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Fail with `error` unless `account` was passed as writable
+pub fn check_writable(account: &AccountInfo, error: impl Into<ProgramError>) -> ProgramResult {
+    if !account.is_writable {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Fail with `error` unless `account` is at `expected`
+pub fn check_key(
+    account: &AccountInfo,
+    expected: &Pubkey,
+    error: impl Into<ProgramError>,
+) -> ProgramResult {
+    if account.key != expected {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Fail with `IncorrectProgramId` unless `account` is the program at `program_id`
+pub fn check_program(account: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    if account.key != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Fail with `error` when `a` and `b` are the same account
+pub fn check_distinct(
+    a: &AccountInfo,
+    b: &AccountInfo,
+    error: impl Into<ProgramError>,
+) -> ProgramResult {
+    if a.key == b.key {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Create `new_account` with `space` bytes owned by `owner`, `payer` funds it rent exempt.
+/// `signer_seeds` are the seeds of `new_account` when it is a PDA, empty for a keypair account.
+/// An address already holding lamports can't go through `create_account`, anyone can fund a
+/// known address first: it is topped up to the rent instead, then allocated and assigned
+pub fn create_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    owner: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    if new_account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                new_account.key,
+                required_lamports,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), new_account.clone(), system_program.clone()],
+            signer_seeds,
+        );
+    }
+
+    let missing_lamports = required_lamports.saturating_sub(new_account.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, new_account.key, missing_lamports),
+            &[payer.clone(), new_account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(new_account.key, space as u64),
+        &[new_account.clone(), system_program.clone()],
+        signer_seeds,
+    )?;
+    invoke_signed(
+        &system_instruction::assign(new_account.key, owner),
+        &[new_account.clone(), system_program.clone()],
+        signer_seeds,
+    )
+}
+
+/// Grow or shrink `account` to `new_len` bytes, `payer` tops it up to stay rent exempt
+pub fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    new_len: usize,
+) -> ProgramResult {
+    let required_lamports = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    account.realloc(new_len, false)
+}
+
+/// Move every lamport of `source` to `destination` and give the account back to the system
+/// program, its data wiped so that refunding it later in the same transaction doesn't revive it
+pub fn close_account(source: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    if source.key == destination.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let dest_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() = dest_lamports
+        .checked_add(source.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **source.lamports.borrow_mut() = 0;
+
+    source.data.borrow_mut().fill(0);
+    source.assign(&system_program::ID);
+    source.realloc(0, false)
+}
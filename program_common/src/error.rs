@@ -0,0 +1,54 @@
+use solana_program::program_error::ProgramError;
+
+/// Implemented by the `CustomError` of each program, a variant is reported as
+/// `ProgramError::Custom` with its discriminant as code
+pub trait ErrorCode: Copy + 'static {
+    /// Every error of the program
+    const ALL: &'static [Self];
+
+    fn code(self) -> u32;
+
+    /// Error reported with `code`, if the program has one
+    fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|error| error.code() == code)
+    }
+
+    /// Error carried by `error`, if it is one of the program custom errors
+    fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Declare a fieldless error enum along with its [ErrorCode] and its conversion to
+/// `ProgramError`, the enum must derive `Clone` and `Copy`
+#[macro_export]
+macro_rules! error_code {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $error:ident {
+            $($(#[$variant_meta:meta])* $variant:ident),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $error {
+            $($(#[$variant_meta])* $variant),*
+        }
+
+        impl $crate::error::ErrorCode for $error {
+            const ALL: &'static [Self] = &[$($error::$variant),*];
+
+            fn code(self) -> u32 {
+                self as u32
+            }
+        }
+
+        impl From<$error> for $crate::solana_program::program_error::ProgramError {
+            fn from(e: $error) -> Self {
+                $crate::solana_program::program_error::ProgramError::Custom(e as u32)
+            }
+        }
+    };
+}
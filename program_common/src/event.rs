@@ -0,0 +1,59 @@
+#[cfg(feature = "decoder")]
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "decoder")]
+use solana_program::pubkey::Pubkey;
+use solana_program::{entrypoint::ProgramResult, log::sol_log_data};
+
+/// Prefix the runtime puts before the base64 fields logged by `sol_log_data`
+pub const LOG_DATA_PREFIX: &str = "Program data: ";
+
+/// Implemented by the event enum of each program. Events are logged Borsh encoded through
+/// `sol_log_data` and read back from the `Program data:` lines of the transaction logs, the
+/// reading side is only built with the `decoder` feature
+pub trait Event: BorshSerialize + BorshDeserialize {
+    fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&borsh::to_vec(self)?]);
+        Ok(())
+    }
+
+    /// Event of a `Program data:` log line, `None` for any other line or data
+    #[cfg(feature = "decoder")]
+    fn from_log(line: &str) -> Option<Self> {
+        let data = line.strip_prefix(LOG_DATA_PREFIX)?;
+        let bytes = STANDARD.decode(data).ok()?;
+        Self::try_from_slice(&bytes).ok()
+    }
+
+    /// Events logged by `program_id` in the `logs` of a transaction, in order. Data logged by
+    /// the other programs, including the ones `program_id` invokes, is skipped
+    #[cfg(feature = "decoder")]
+    fn decode_logs(program_id: &Pubkey, logs: &[String]) -> Vec<Self> {
+        let program_id = program_id.to_string();
+        // programs invoked so far and not returned yet, the last one is logging
+        let mut invoked: Vec<&str> = Vec::new();
+        let mut events = Vec::new();
+        for line in logs {
+            if line.starts_with(LOG_DATA_PREFIX) {
+                if invoked.last() == Some(&program_id.as_str()) {
+                    events.extend(Self::from_log(line));
+                }
+                continue;
+            }
+            // messages a program logs, `Program log: success` among them, are not the runtime's
+            let Some((program, status)) = line
+                .strip_prefix("Program ")
+                .and_then(|rest| rest.split_once(' '))
+                .filter(|(program, _)| program.parse::<Pubkey>().is_ok())
+            else {
+                continue;
+            };
+            if status.starts_with("invoke [") {
+                invoked.push(program);
+            } else if status == "success" || status.starts_with("failed") {
+                invoked.pop();
+            }
+        }
+        events
+    }
+}
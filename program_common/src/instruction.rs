@@ -0,0 +1,9 @@
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+
+pub trait Unpack: BorshDeserialize {
+    /// Deserialize the instruction data, trailing bytes are ignored
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::deserialize(&mut &input[..]).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
@@ -0,0 +1,24 @@
+//! Helpers shared by the on-chain programs of the workspace
+
+pub mod account;
+pub mod error;
+pub mod event;
+pub mod instruction;
+pub mod state;
+#[cfg(all(feature = "program-test", not(target_os = "solana")))]
+pub mod stubs;
+
+pub use account::{
+    check_distinct, check_key, check_owner, check_program, check_signer, check_writable,
+    close_account, create_account, resize_account,
+};
+pub use error::ErrorCode;
+pub use event::Event;
+pub use instruction::Unpack;
+pub use state::{check_header, AccountHeaderError, AccountSize, ACCOUNT_HEADER_LEN};
+
+#[doc(hidden)]
+pub use solana_program;
+
+#[cfg(test)]
+mod test;
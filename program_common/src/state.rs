@@ -0,0 +1,44 @@
+use borsh::BorshSerialize;
+use solana_program::{entrypoint::ProgramResult, program_error::ProgramError};
+
+/// Size of the discriminator and version leading every account
+pub const ACCOUNT_HEADER_LEN: usize = 8 + 1;
+
+/// Borsh-serialized length of a state type, the exact size of the account holding it
+pub trait AccountSize: BorshSerialize {
+    fn account_size(&self) -> usize;
+
+    /// Serialize into `data`, failing unless it fills the account data exactly
+    fn pack_into(&self, data: &mut [u8]) -> ProgramResult {
+        let len = data.len();
+        let mut writer = data;
+        self.serialize(&mut writer)?;
+        let written = len - writer.len();
+        if written != len || written != self.account_size() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+}
+
+/// Why an account header was refused, each program maps it to its own error codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountHeaderError {
+    InvalidDiscriminator,
+    UnsupportedVersion,
+}
+
+/// Fail unless `data` starts with `discriminator` followed by `version`
+pub fn check_header(
+    data: &[u8],
+    discriminator: &[u8; 8],
+    version: u8,
+) -> Result<(), AccountHeaderError> {
+    if data.len() < ACCOUNT_HEADER_LEN || data[..8] != discriminator[..] {
+        return Err(AccountHeaderError::InvalidDiscriminator);
+    }
+    if data[8] != version {
+        return Err(AccountHeaderError::UnsupportedVersion);
+    }
+    Ok(())
+}
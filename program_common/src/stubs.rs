@@ -0,0 +1,157 @@
+//! Syscall stubs for the tests running programs natively through solana-program-test, built
+//! with the `program-test` feature
+
+use crate::{error::ErrorCode, event::LOG_DATA_PREFIX};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_program_test::ProgramTest;
+use std::sync::Once;
+
+/// Prefix solana-program-test puts before every message logged by a native program
+const PROGRAM_LOG_PREFIX: &str = "Program log: ";
+
+/// Stubs installed by solana-program-test, except that `sol_log_data` writes its line to the
+/// transaction logs instead of printing it
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memcpy(dst, src, n)
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memmove(dst, src, n)
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.0.sol_memcmp(s1, s2, n, result)
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.0.sol_memset(s, c, n)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.0
+            .sol_log(&format!("{}{}", LOG_DATA_PREFIX, fields.join(" ")))
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+/// Have `sol_log_data` of native programs write to the transaction logs, solana-program-test
+/// only prints it. Stubs are global: call it before any transaction runs, it has
+/// solana-program-test install its own stubs by starting a bank, then wraps them. Later calls
+/// do nothing
+pub fn log_data_to_transaction_logs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        struct Placeholder;
+        impl SyscallStubs for Placeholder {}
+
+        // on a thread of its own, the caller may already be inside a runtime
+        std::thread::spawn(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(ProgramTest::default().start())
+        })
+        .join()
+        .unwrap();
+
+        let stubs = set_syscall_stubs(Box::new(Placeholder));
+        set_syscall_stubs(Box::new(LogDataStubs(stubs)));
+    });
+}
+
+/// Transaction `logs` with the data lines written by the stubs of
+/// [log_data_to_transaction_logs] as the runtime writes them for on-chain programs
+pub fn runtime_logs(logs: &[String]) -> Vec<String> {
+    logs.iter()
+        .map(|line| match line.strip_prefix(PROGRAM_LOG_PREFIX) {
+            Some(data) if data.starts_with(LOG_DATA_PREFIX) => data.to_string(),
+            _ => line.clone(),
+        })
+        .collect()
+}
+
+/// Every way to break the accounts of `instruction` along with the error it must fail with:
+/// each trailing account left out, each writable account passed read-only, failing with
+/// `not_writable`, and the system program swapped for another program
+pub fn broken_accounts(
+    instruction: &Instruction,
+    not_writable: impl ErrorCode,
+) -> Vec<(Instruction, InstructionError)> {
+    let mut broken = Vec::new();
+    for len in 0..instruction.accounts.len() {
+        let mut missing = instruction.clone();
+        missing.accounts.truncate(len);
+        broken.push((missing, InstructionError::NotEnoughAccountKeys));
+    }
+    for (i, meta) in instruction.accounts.iter().enumerate() {
+        if meta.is_writable {
+            let mut read_only = instruction.clone();
+            read_only.accounts[i].is_writable = false;
+            broken.push((read_only, InstructionError::Custom(not_writable.code())));
+        }
+        if meta.pubkey == system_program::ID {
+            for wrong_program in [instruction.program_id, Pubkey::new_unique()] {
+                let mut wrong = instruction.clone();
+                wrong.accounts[i] = AccountMeta::new_readonly(wrong_program, false);
+                broken.push((wrong, InstructionError::IncorrectProgramId));
+            }
+        }
+    }
+    broken
+}
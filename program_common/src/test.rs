@@ -0,0 +1,196 @@
+#[cfg(feature = "decoder")]
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use super::*;
+
+error_code! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum TestError {
+        First,
+        Second,
+        Third,
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[borsh(use_discriminant = true)]
+#[repr(u8)]
+enum TestInstruction {
+    Ping = 0,
+    Set { value: i64 } = 1,
+}
+
+impl Unpack for TestInstruction {}
+
+#[cfg(feature = "decoder")]
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[borsh(use_discriminant = true)]
+#[repr(u8)]
+enum TestEvent {
+    Changed { value: i64 } = 0,
+    Closed = 1,
+}
+
+#[cfg(feature = "decoder")]
+impl Event for TestEvent {}
+
+#[test]
+fn test_error_code() {
+    for (error, code) in [
+        (TestError::First, 0),
+        (TestError::Second, 1),
+        (TestError::Third, 2),
+    ] {
+        assert_eq!(error.code(), code);
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        assert_eq!(TestError::from_code(code), Some(error));
+    }
+    assert_eq!(TestError::ALL.len(), 3);
+    assert_eq!(TestError::from_code(3), None);
+    assert_eq!(
+        TestError::from_program_error(&ProgramError::Custom(2)),
+        Some(TestError::Third)
+    );
+    assert_eq!(
+        TestError::from_program_error(&ProgramError::InvalidArgument),
+        None
+    );
+}
+
+#[test]
+fn test_unpack() {
+    let data = borsh::to_vec(&TestInstruction::Set { value: -7 }).unwrap();
+    assert_eq!(
+        TestInstruction::unpack(&data).unwrap(),
+        TestInstruction::Set { value: -7 }
+    );
+    assert_eq!(
+        TestInstruction::unpack(&[0]).unwrap(),
+        TestInstruction::Ping
+    );
+    for data in [&[][..], &[2], &[1, 0]] {
+        assert_eq!(
+            TestInstruction::unpack(data).unwrap_err(),
+            ProgramError::InvalidInstructionData
+        );
+    }
+}
+
+#[test]
+fn test_check_header() {
+    let discriminator = *b"test\0\0\0\0";
+    let mut data = discriminator.to_vec();
+    data.extend_from_slice(&[1, 42]);
+
+    assert_eq!(check_header(&data, &discriminator, 1), Ok(()));
+    assert_eq!(
+        check_header(&data, &discriminator, 2),
+        Err(AccountHeaderError::UnsupportedVersion)
+    );
+    assert_eq!(
+        check_header(&data, b"other\0\0\0", 1),
+        Err(AccountHeaderError::InvalidDiscriminator)
+    );
+    assert_eq!(
+        check_header(&data[..8], &discriminator, 1),
+        Err(AccountHeaderError::InvalidDiscriminator)
+    );
+}
+
+#[test]
+fn test_account_checks() {
+    let key = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let mut lamports = 0;
+    let mut data = [];
+    let account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+
+    assert_eq!(check_owner(&account, &owner), Ok(()));
+    assert_eq!(
+        check_owner(&account, &key),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    assert_eq!(
+        check_signer(&account, TestError::Second),
+        Err(ProgramError::Custom(1))
+    );
+    assert_eq!(
+        check_writable(&account, TestError::First),
+        Err(ProgramError::Custom(0))
+    );
+    assert_eq!(check_program(&account, &key), Ok(()));
+    assert_eq!(
+        check_program(&account, &owner),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    assert_eq!(
+        check_distinct(&account, &account, TestError::Second),
+        Err(ProgramError::Custom(1))
+    );
+    assert_eq!(check_key(&account, &key, TestError::First), Ok(()));
+    assert_eq!(
+        check_key(&account, &owner, TestError::Third),
+        Err(ProgramError::Custom(2))
+    );
+}
+
+#[cfg(feature = "decoder")]
+#[test]
+fn test_decode_event_logs() {
+    let program_id = Pubkey::new_unique();
+    let other_program = Pubkey::new_unique();
+    let data = |event: &TestEvent| {
+        format!(
+            "{}{}",
+            event::LOG_DATA_PREFIX,
+            STANDARD.encode(borsh::to_vec(event).unwrap())
+        )
+    };
+    let logs = vec![
+        format!("Program {} invoke [1]", program_id),
+        "Program log: counter set".to_string(),
+        data(&TestEvent::Changed { value: -3 }),
+        // messages logged by the programs only look like the runtime lines
+        "Program log: invoke [2]".to_string(),
+        // data logged by a program it invokes is not its own
+        format!("Program {} invoke [2]", other_program),
+        "Program log: success".to_string(),
+        data(&TestEvent::Changed { value: 100 }),
+        format!("Program {} success", other_program),
+        data(&TestEvent::Closed),
+        "Program data: not base64".to_string(),
+        format!("{}{}", event::LOG_DATA_PREFIX, STANDARD.encode([9])),
+        format!(
+            "Program {} consumed 1200 of 200000 compute units",
+            program_id
+        ),
+        format!("Program {} success", program_id),
+        format!("Program {} invoke [1]", other_program),
+        data(&TestEvent::Closed),
+        format!(
+            "Program {} failed: custom program error: 0x0",
+            other_program
+        ),
+    ];
+
+    assert_eq!(
+        TestEvent::decode_logs(&program_id, &logs),
+        vec![TestEvent::Changed { value: -3 }, TestEvent::Closed]
+    );
+    assert_eq!(
+        TestEvent::decode_logs(&other_program, &logs),
+        vec![TestEvent::Changed { value: 100 }, TestEvent::Closed]
+    );
+    assert_eq!(TestEvent::from_log("Program log: counter set"), None);
+}
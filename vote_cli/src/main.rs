@@ -0,0 +1,285 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use cli_common::SolanaConfig;
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use std::{
+    error::Error,
+    path::PathBuf,
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use vote_client::{Tally, VoteClient};
+
+/// Run polls with the vote program
+#[derive(Parser, Debug)]
+#[command(name = "vote-cli", version)]
+struct Cli {
+    /// Solana CLI config file [default: ~/.config/solana/cli/config.yml]
+    #[arg(short = 'C', long, global = true)]
+    config: Option<PathBuf>,
+    /// RPC url, overrides the config file
+    #[arg(short, long, global = true)]
+    url: Option<String>,
+    /// Keypair owning the Votes and casting the ballots, pays every fee, overrides the config
+    /// file
+    #[arg(short, long, global = true)]
+    keypair: Option<PathBuf>,
+    /// Address of the deployed vote program
+    #[arg(long, env = "VOTE_PROGRAM_ID", global = true)]
+    program_id: Option<Pubkey>,
+    /// Print results as a table or as one JSON value
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create a Vote owned by the keypair
+    Create {
+        /// Labels of the options, in index order
+        #[arg(required = true, value_name = "LABEL")]
+        options: Vec<String>,
+        /// Unix timestamp closing the Vote
+        #[arg(long, required_unless_present = "duration", allow_hyphen_values = true)]
+        end_date: Option<i64>,
+        /// Seconds the Vote stays open from now, instead of --end-date
+        #[arg(long, conflicts_with = "end_date")]
+        duration: Option<i64>,
+        /// Unix timestamp opening the Vote [default: creation time]
+        #[arg(long, allow_hyphen_values = true)]
+        start_date: Option<i64>,
+    },
+    /// Cast the ballot of the keypair
+    Vote {
+        vote: Pubkey,
+        /// Index of the option
+        option: u8,
+    },
+    /// Withdraw the ballot of the keypair, or reclaim its rent once the Vote is over
+    Unvote { vote: Pubkey },
+    /// Close a Vote of the keypair and reclaim its rent
+    Close {
+        vote: Pubkey,
+        /// Receives the rent of the Vote [default: the keypair]
+        #[arg(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Print the tally of a Vote
+    Show { vote: Pubkey },
+    /// List the Votes of an owner
+    List {
+        /// Owner of the Votes [default: the keypair]
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+impl Command {
+    /// Whether the command needs the keypair, to sign or to know whose Votes to list
+    fn needs_keypair(&self) -> bool {
+        !matches!(
+            self,
+            Command::Show { .. } | Command::List { owner: Some(_) }
+        )
+    }
+}
+
+/// Timestamp as printed in tables, migrated Votes have no start date
+fn date_text(date: i64) -> String {
+    match date {
+        i64::MIN => "-".to_string(),
+        date => date.to_string(),
+    }
+}
+
+/// Left-align `rows` under `headers`, columns separated by two spaces
+fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut lines = vec![line(headers.to_vec())];
+    lines.extend(
+        rows.iter()
+            .map(|row| line(row.iter().map(String::as_str).collect())),
+    );
+    lines.join("\n")
+}
+
+fn tally_json(address: &Pubkey, owner: &Pubkey, tally: &Tally) -> Value {
+    json!({
+        "address": address.to_string(),
+        "owner": owner.to_string(),
+        "start_date": (tally.start_date != i64::MIN).then_some(tally.start_date),
+        "end_date": tally.end_date,
+        "options": tally.options.iter().map(|option| json!({
+            "label": option.label,
+            "votes": option.votes,
+        })).collect::<Vec<_>>(),
+        "total": tally.total(),
+    })
+}
+
+fn tally_table(address: &Pubkey, owner: &Pubkey, tally: &Tally) -> String {
+    let rows: Vec<Vec<String>> = tally
+        .options
+        .iter()
+        .enumerate()
+        .map(|(index, option)| {
+            vec![
+                index.to_string(),
+                option.label.clone(),
+                option.votes.to_string(),
+            ]
+        })
+        .collect();
+    format!(
+        "Vote: {}\nOwner: {}\nOpen: {} to {}\n\n{}\n\nTotal: {}",
+        address,
+        owner,
+        date_text(tally.start_date),
+        tally.end_date,
+        format_table(&["#", "OPTION", "VOTES"], &rows),
+        tally.total()
+    )
+}
+
+fn print(output: OutputFormat, json: Value, table: String) {
+    match output {
+        OutputFormat::Table => println!("{}", table),
+        OutputFormat::Json => println!("{}", json),
+    }
+}
+
+/// Print the signature of a confirmed transaction
+fn print_signature(output: OutputFormat, action: &str, vote: &Pubkey, signature: impl ToString) {
+    let signature = signature.to_string();
+    print(
+        output,
+        json!({ "vote": vote.to_string(), "signature": signature }),
+        format!("{}: {}\nSignature: {}", action, vote, signature),
+    );
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let config = SolanaConfig::load(cli.config.as_ref())?;
+    let program_id = cli
+        .program_id
+        .ok_or("missing --program-id or VOTE_PROGRAM_ID")?;
+
+    // reading Votes never signs, a throwaway keypair stands in when none is needed
+    let keypair = if cli.command.needs_keypair() {
+        config.read_keypair(cli.keypair)?
+    } else {
+        Keypair::new()
+    };
+    let client = VoteClient::new(config.rpc_client(cli.url)?, program_id, keypair);
+
+    match cli.command {
+        Command::Create {
+            options,
+            end_date,
+            duration,
+            start_date,
+        } => {
+            let end_date = match (end_date, duration) {
+                (Some(end_date), _) => end_date,
+                (None, Some(duration)) => {
+                    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+                    now.checked_add(duration).ok_or("duration is too long")?
+                }
+                (None, None) => unreachable!("clap requires --end-date or --duration"),
+            };
+            let created = client.create(end_date, start_date, options).await?;
+            print_signature(cli.output, "Vote", &created.address, created.signature);
+        }
+        Command::Vote { vote, option } => {
+            let signature = client.vote(&vote, option).await?;
+            print_signature(cli.output, "Voted on", &vote, signature);
+        }
+        Command::Unvote { vote } => {
+            let signature = client.unvote(&vote).await?;
+            print_signature(cli.output, "Unvoted on", &vote, signature);
+        }
+        Command::Close { vote, destination } => {
+            let destination = destination.unwrap_or_else(|| client.signer().pubkey());
+            let signature = client.close(&vote, &destination).await?;
+            print_signature(cli.output, "Closed", &vote, signature);
+        }
+        Command::Show { vote } => {
+            let vote_account = client.fetch(&vote).await?;
+            let owner = vote_account.owner;
+            let tally = Tally::from(vote_account);
+            print(
+                cli.output,
+                tally_json(&vote, &owner, &tally),
+                tally_table(&vote, &owner, &tally),
+            );
+        }
+        Command::List { owner } => {
+            let owner = owner.unwrap_or_else(|| client.signer().pubkey());
+            let mut votes = client.list(&owner).await?;
+            votes.sort_by_key(|(address, vote)| (vote.end_date, *address));
+            let tallies: Vec<(Pubkey, Tally)> = votes
+                .into_iter()
+                .map(|(address, vote)| (address, Tally::from(vote)))
+                .collect();
+            let json = tallies
+                .iter()
+                .map(|(address, tally)| tally_json(address, &owner, tally))
+                .collect();
+            let rows: Vec<Vec<String>> = tallies
+                .iter()
+                .map(|(address, tally)| {
+                    vec![
+                        address.to_string(),
+                        date_text(tally.start_date),
+                        tally.end_date.to_string(),
+                        tally.options.len().to_string(),
+                        tally.total().to_string(),
+                    ]
+                })
+                .collect();
+            print(
+                cli.output,
+                Value::Array(json),
+                format_table(&["VOTE", "START", "END", "OPTIONS", "BALLOTS"], &rows),
+            );
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
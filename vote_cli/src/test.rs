@@ -0,0 +1,108 @@
+use clap::CommandFactory;
+use vote_client::VoteOption;
+
+use super::*;
+
+#[test]
+fn test_cli_definition() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn test_parse_create() {
+    let cli = Cli::try_parse_from([
+        "vote-cli",
+        "create",
+        "yes",
+        "no",
+        "--duration",
+        "3600",
+        "--output",
+        "json",
+    ])
+    .unwrap();
+    assert_eq!(cli.output, OutputFormat::Json);
+    assert!(matches!(
+        cli.command,
+        Command::Create {
+            options,
+            end_date: None,
+            duration: Some(3600),
+            start_date: None,
+        } if options == ["yes", "no"]
+    ));
+
+    // the end of the Vote is given once, as a date or a duration
+    assert!(Cli::try_parse_from(["vote-cli", "create", "yes"]).is_err());
+    assert!(Cli::try_parse_from([
+        "vote-cli",
+        "create",
+        "yes",
+        "--end-date",
+        "10",
+        "--duration",
+        "10",
+    ])
+    .is_err());
+    assert!(Cli::try_parse_from(["vote-cli", "create", "--end-date", "10"]).is_err());
+}
+
+#[test]
+fn test_needs_keypair() {
+    let vote = Pubkey::new_unique().to_string();
+    for (args, needs_keypair) in [
+        (vec!["vote-cli", "show", &vote], false),
+        (vec!["vote-cli", "list", "--owner", &vote], false),
+        (vec!["vote-cli", "list"], true),
+        (vec!["vote-cli", "vote", &vote, "1"], true),
+    ] {
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.command.needs_keypair(), needs_keypair);
+    }
+}
+
+#[test]
+fn test_format_table() {
+    let table = format_table(
+        &["#", "OPTION", "VOTES"],
+        &[
+            vec!["0".to_string(), "yes".to_string(), "12".to_string()],
+            vec!["1".to_string(), "abstain".to_string(), "3".to_string()],
+        ],
+    );
+    assert_eq!(
+        table,
+        "#  OPTION   VOTES\n\
+         0  yes      12\n\
+         1  abstain  3"
+    );
+}
+
+#[test]
+fn test_tally_output() {
+    let address = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let tally = Tally {
+        start_date: i64::MIN,
+        end_date: 100,
+        options: vec![
+            VoteOption {
+                label: "yes".to_string(),
+                votes: 2,
+            },
+            VoteOption {
+                label: "no".to_string(),
+                votes: 1,
+            },
+        ],
+    };
+
+    let json = tally_json(&address, &owner, &tally);
+    assert_eq!(json["start_date"], Value::Null);
+    assert_eq!(json["options"][1]["label"], "no");
+    assert_eq!(json["total"], 3);
+
+    let table = tally_table(&address, &owner, &tally);
+    assert!(table.contains("Open: - to 100"));
+    assert!(table.ends_with("Total: 3"));
+}
@@ -0,0 +1,82 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use vote_client::VoteClient;
+
+#[tokio::main]
+async fn main() {
+    let keypair = read_keypair_file("../target/deploy/vote_program-keypair.json").unwrap();
+    let program_pubkey = keypair.pubkey();
+
+    // local validator unless another RPC url is given
+    let rpc_url = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "http://127.0.0.1:8899".to_string());
+    let rpc = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+
+    // Generate a new keypair for the payer and fund it
+    let payer = Keypair::new();
+    let airdrop_amount = 1_000_000_000; // 1 SOL
+    let signature = rpc
+        .request_airdrop(&payer.pubkey(), airdrop_amount)
+        .await
+        .expect("Failed to request airdrop");
+    rpc.poll_for_signature(&signature)
+        .await
+        .expect("Airdrop was not confirmed");
+
+    let client = VoteClient::new(rpc, program_pubkey, payer);
+
+    // Voting stays open for one day
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let created = match client
+        .create(
+            now + 24 * 60 * 60,
+            None,
+            vec!["yes".to_string(), "no".to_string()],
+        )
+        .await
+    {
+        Ok(created) => created,
+        Err(err) => {
+            eprintln!("Error creating vote: {}", err);
+            return;
+        }
+    };
+    println!(
+        "Vote {} created, signature: {}",
+        created.address, created.signature
+    );
+
+    match client.vote(&created.address, 0).await {
+        Ok(signature) => println!("Ballot cast, signature: {}", signature),
+        Err(err) => eprintln!("Error casting ballot: {}", err),
+    }
+
+    match client.fetch_tally(&created.address).await {
+        Ok(tally) => {
+            for option in &tally.options {
+                println!("{}: {}", option.label, option.votes);
+            }
+        }
+        Err(err) => eprintln!("Error fetching tally: {}", err),
+    }
+
+    match client.unvote(&created.address).await {
+        Ok(signature) => println!("Ballot withdrawn, signature: {}", signature),
+        Err(err) => eprintln!("Error withdrawing ballot: {}", err),
+    }
+
+    let destination = client.signer().pubkey();
+    match client.close(&created.address, &destination).await {
+        Ok(signature) => println!("Vote closed, signature: {}", signature),
+        Err(err) => eprintln!("Error closing vote: {}", err),
+    }
+}
@@ -0,0 +1,202 @@
+//! Async client of the vote program
+
+pub use vote_program::state::{VoteAccount, VoteOption};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use vote_program::{error::CustomError, instruction, state::find_ballot_address};
+
+/// Error of every request of [VoteClient]
+pub type ClientError = client_common::ClientError<CustomError>;
+
+/// Vote created by [VoteClient::create]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatedVote {
+    pub address: Pubkey,
+    pub signature: Signature,
+}
+
+/// Votes counted so far on each option of a Vote
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tally {
+    pub start_date: i64,
+    pub end_date: i64,
+    pub options: Vec<VoteOption>,
+}
+
+impl From<VoteAccount> for Tally {
+    fn from(vote: VoteAccount) -> Self {
+        Self {
+            start_date: vote.start_date,
+            end_date: vote.end_date,
+            options: vote.options,
+        }
+    }
+}
+
+impl Tally {
+    /// Ballots cast on every option
+    pub fn total(&self) -> u64 {
+        self.options
+            .iter()
+            .fold(0, |total, option| total.saturating_add(option.votes))
+    }
+}
+
+/// Send vote instructions through `rpc`, `signer` pays for every transaction, owns the Votes it
+/// creates and casts its own ballots
+pub struct VoteClient {
+    rpc: RpcClient,
+    program_id: Pubkey,
+    signer: Keypair,
+}
+
+impl VoteClient {
+    pub fn new(rpc: RpcClient, program_id: Pubkey, signer: Keypair) -> Self {
+        Self {
+            rpc,
+            program_id,
+            signer,
+        }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub fn signer(&self) -> &Keypair {
+        &self.signer
+    }
+
+    /// Address of the ballot of the client signer on `vote`
+    pub fn ballot_address(&self, vote: &Pubkey) -> Pubkey {
+        find_ballot_address(&self.program_id, vote, &self.signer.pubkey()).0
+    }
+
+    /// Create a Vote on a fresh keypair account, open from `start_date` (creation time when
+    /// unset) until `end_date`
+    pub async fn create(
+        &self,
+        end_date: i64,
+        start_date: Option<i64>,
+        options: Vec<String>,
+    ) -> Result<CreatedVote, ClientError> {
+        let vote = Keypair::new();
+        let instruction = instruction::create_vote(
+            &self.program_id,
+            &vote.pubkey(),
+            &self.signer.pubkey(),
+            end_date,
+            start_date,
+            options,
+        );
+        let signature = self.send(instruction, Some(&vote)).await?;
+        Ok(CreatedVote {
+            address: vote.pubkey(),
+            signature,
+        })
+    }
+
+    /// Cast the ballot of the client signer for `option`
+    pub async fn vote(&self, vote: &Pubkey, option: u8) -> Result<Signature, ClientError> {
+        let instruction =
+            instruction::add_vote(&self.program_id, vote, &self.signer.pubkey(), option);
+        self.send(instruction, None).await
+    }
+
+    /// Withdraw the ballot of the client signer, once `vote` is over only its rent is reclaimed
+    pub async fn unvote(&self, vote: &Pubkey) -> Result<Signature, ClientError> {
+        let instruction = instruction::remove_vote(&self.program_id, vote, &self.signer.pubkey());
+        self.send(instruction, None).await
+    }
+
+    /// Close `vote` and send its rent to `destination`, the client signer must own it
+    pub async fn close(
+        &self,
+        vote: &Pubkey,
+        destination: &Pubkey,
+    ) -> Result<Signature, ClientError> {
+        let instruction =
+            instruction::close_vote(&self.program_id, vote, destination, &self.signer.pubkey());
+        self.send(instruction, None).await
+    }
+
+    pub async fn fetch(&self, vote: &Pubkey) -> Result<VoteAccount, ClientError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(vote, self.rpc.commitment())
+            .await?
+            .value
+            .filter(|account| account.owner == self.program_id)
+            .ok_or(ClientError::AccountNotFound(*vote))?;
+        VoteAccount::unpack(&account.data).map_err(|e| ClientError::InvalidAccount(*vote, e))
+    }
+
+    pub async fn fetch_tally(&self, vote: &Pubkey) -> Result<Tally, ClientError> {
+        Ok(self.fetch(vote).await?.into())
+    }
+
+    /// Every Vote owned by `owner`, in no particular order
+    pub async fn list(&self, owner: &Pubkey) -> Result<Vec<(Pubkey, VoteAccount)>, ClientError> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &VoteAccount::DISCRIMINATOR)),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    VoteAccount::OWNER_OFFSET,
+                    owner.as_ref(),
+                )),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, config)
+            .await?;
+        let mut votes = Vec::with_capacity(accounts.len());
+        for (address, account) in accounts {
+            let vote = VoteAccount::unpack(&account.data)
+                .map_err(|e| ClientError::InvalidAccount(address, e))?;
+            votes.push((address, vote));
+        }
+        Ok(votes)
+    }
+
+    async fn send(
+        &self,
+        instruction: Instruction,
+        extra_signer: Option<&Keypair>,
+    ) -> Result<Signature, ClientError> {
+        let mut signers = vec![&self.signer];
+        signers.extend(extra_signer);
+        let recent_blockhash = self.rpc.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.signer.pubkey()),
+            &signers,
+            recent_blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx).await?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,48 @@
+use solana_client::client_error::ClientError as RpcClientError;
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+use vote_program::error::CustomError;
+
+use super::*;
+
+#[test]
+fn test_decode_program_error() {
+    let error = ClientError::from(RpcClientError::from(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(CustomError::VoteClosed as u32),
+    )));
+    assert!(matches!(
+        error,
+        ClientError::Program(CustomError::VoteClosed)
+    ));
+
+    // codes the program doesn't define and runtime errors are left as they are
+    for error in [
+        TransactionError::InstructionError(0, InstructionError::Custom(u32::MAX)),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+        TransactionError::AccountNotFound,
+    ] {
+        assert!(matches!(
+            ClientError::from(RpcClientError::from(error)),
+            ClientError::Rpc(_)
+        ));
+    }
+}
+
+#[test]
+fn test_tally_total() {
+    let tally = Tally {
+        start_date: 0,
+        end_date: 1,
+        options: vec![
+            VoteOption {
+                label: "yes".to_string(),
+                votes: 3,
+            },
+            VoteOption {
+                label: "no".to_string(),
+                votes: 2,
+            },
+        ],
+    };
+    assert_eq!(tally.total(), 5);
+}
@@ -0,0 +1,180 @@
+use crate::{error::CustomError, state::find_ballot_address};
+use program_common::{
+    check_distinct, check_key, check_owner, check_program, check_signer, check_writable,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// NOTE:: Accounts of CreateVote
+pub struct CreateVoteAccounts<'a, 'info> {
+    /// NOTE:: New keypair account holding the Vote, it signs its own creation
+    pub vote: &'a AccountInfo<'info>,
+    /// NOTE:: Pays for the Vote and becomes its owner
+    pub payer: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> CreateVoteAccounts<'a, 'info> {
+    pub fn try_from(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let vote = next_account_info(accounts_iter)?;
+        let payer = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        check_signer(vote, ProgramError::MissingRequiredSignature)?;
+        check_writable(vote, CustomError::AccountNotWritable)?;
+        check_signer(payer, CustomError::MissingOwnerSignature)?;
+        check_writable(payer, CustomError::AccountNotWritable)?;
+        check_program(system_program, &system_program::ID)?;
+        Ok(Self {
+            vote,
+            payer,
+            system_program,
+        })
+    }
+}
+
+/// NOTE:: Accounts of CloseVote
+pub struct CloseVoteAccounts<'a, 'info> {
+    pub vote: &'a AccountInfo<'info>,
+    /// NOTE:: Receives the lamports of the Vote
+    pub destination: &'a AccountInfo<'info>,
+    /// NOTE:: Must sign, checked against the Vote owner by the processor
+    pub owner: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> CloseVoteAccounts<'a, 'info> {
+    pub fn try_from(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let vote = next_account_info(accounts_iter)?;
+        let destination = next_account_info(accounts_iter)?;
+        let owner = next_account_info(accounts_iter)?;
+
+        check_owner(vote, program_id)?;
+        check_writable(vote, CustomError::AccountNotWritable)?;
+        check_writable(destination, CustomError::AccountNotWritable)?;
+        check_distinct(vote, destination, CustomError::DuplicateAccount)?;
+        check_signer(owner, CustomError::MissingOwnerSignature)?;
+        Ok(Self {
+            vote,
+            destination,
+            owner,
+        })
+    }
+}
+
+/// NOTE:: Accounts of AddVote
+pub struct AddVoteAccounts<'a, 'info> {
+    pub vote: &'a AccountInfo<'info>,
+    /// NOTE:: Casts the ballot and pays for it
+    pub voter: &'a AccountInfo<'info>,
+    /// NOTE:: Ballot PDA of the voter on this Vote, created by the instruction
+    pub ballot: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    /// NOTE:: Bump seed of the ballot PDA
+    pub ballot_bump: u8,
+}
+
+impl<'a, 'info> AddVoteAccounts<'a, 'info> {
+    pub fn try_from(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let vote = next_account_info(accounts_iter)?;
+        let voter = next_account_info(accounts_iter)?;
+        let ballot = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        check_owner(vote, program_id)?;
+        check_writable(vote, CustomError::AccountNotWritable)?;
+        check_signer(voter, CustomError::MissingVoterSignature)?;
+        check_writable(voter, CustomError::AccountNotWritable)?;
+        let (ballot_key, ballot_bump) = find_ballot_address(program_id, vote.key, voter.key);
+        check_key(ballot, &ballot_key, CustomError::InvalidBallotAccount)?;
+        check_writable(ballot, CustomError::AccountNotWritable)?;
+        check_program(system_program, &system_program::ID)?;
+        Ok(Self {
+            vote,
+            voter,
+            ballot,
+            system_program,
+            ballot_bump,
+        })
+    }
+}
+
+/// NOTE:: Accounts of RemoveVote
+pub struct RemoveVoteAccounts<'a, 'info> {
+    /// NOTE:: Vote of the ballot, or its address once closed
+    pub vote: &'a AccountInfo<'info>,
+    /// NOTE:: Withdraws the ballot and gets its rent back
+    pub voter: &'a AccountInfo<'info>,
+    /// NOTE:: Ballot of the voter, checked against its data by the processor
+    pub ballot: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> RemoveVoteAccounts<'a, 'info> {
+    pub fn try_from(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let vote = next_account_info(accounts_iter)?;
+        let voter = next_account_info(accounts_iter)?;
+        let ballot = next_account_info(accounts_iter)?;
+
+        // NOTE:: the Vote may be closed already, the ballot data ties it to the Vote
+        check_writable(vote, CustomError::AccountNotWritable)?;
+        check_signer(voter, CustomError::MissingVoterSignature)?;
+        check_writable(voter, CustomError::AccountNotWritable)?;
+        if ballot.owner != program_id {
+            return Err(CustomError::BallotNotFound.into());
+        }
+        check_writable(ballot, CustomError::AccountNotWritable)?;
+        Ok(Self {
+            vote,
+            voter,
+            ballot,
+        })
+    }
+}
+
+/// NOTE:: Accounts of Migrate
+pub struct MigrateVoteAccounts<'a, 'info> {
+    pub vote: &'a AccountInfo<'info>,
+    /// NOTE:: Must sign, checked against the legacy Vote owner by the processor, pays for the
+    /// NOTE:: extra rent
+    pub owner: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> MigrateVoteAccounts<'a, 'info> {
+    pub fn try_from(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let vote = next_account_info(accounts_iter)?;
+        let owner = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        check_owner(vote, program_id)?;
+        check_writable(vote, CustomError::AccountNotWritable)?;
+        check_signer(owner, CustomError::MissingOwnerSignature)?;
+        check_writable(owner, CustomError::AccountNotWritable)?;
+        check_program(system_program, &system_program::ID)?;
+        Ok(Self {
+            vote,
+            owner,
+            system_program,
+        })
+    }
+}
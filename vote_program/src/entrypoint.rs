@@ -1,14 +1,18 @@
 use instructions::VoteInstruction;
 use processor::*;
-use solana_program::entrypoint;
+use program_common::Unpack;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 
+pub mod accounts;
 pub mod error;
+pub mod events;
+pub mod instruction;
 pub mod instructions;
 pub mod processor;
 pub mod state;
 
-entrypoint!(entrypoints);
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(entrypoints);
 pub fn entrypoints(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -16,10 +20,15 @@ pub fn entrypoints(
 ) -> ProgramResult {
     let instruction = VoteInstruction::unpack(instruction_data)?;
     match instruction {
-        VoteInstruction::CreateVote => process_create_vote(program_id, accounts),
+        VoteInstruction::CreateVote {
+            end_date,
+            start_date,
+            options,
+        } => process_create_vote(program_id, accounts, end_date, start_date, options),
         VoteInstruction::CloseVote => process_close_vote(program_id, accounts),
-        VoteInstruction::AddVote => process_add_vote(program_id, accounts),
+        VoteInstruction::AddVote { option } => process_add_vote(program_id, accounts, option),
         VoteInstruction::RemoveVote => process_remove_vote(program_id, accounts),
+        VoteInstruction::Migrate => process_migrate_vote(program_id, accounts),
     }
 }
 
@@ -1,11 +1,55 @@
-use solana_program::program_error::ProgramError;
+use program_common::AccountHeaderError;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
-pub enum CustomError {}
+program_common::error_code! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+    pub enum CustomError {
+        #[error("Vote owner did not sign the transaction")]
+        MissingOwnerSignature,
+        #[error("Signer is not the vote owner")]
+        Unauthorized,
+        #[error("Voter did not sign the transaction")]
+        MissingVoterSignature,
+        #[error("Ballot account does not match the vote and voter")]
+        InvalidBallotAccount,
+        #[error("Voter already cast a ballot on this vote")]
+        AlreadyVoted,
+        #[error("Voter has no ballot on this vote")]
+        BallotNotFound,
+        #[error("Vote end date must be in the future and after its start date")]
+        InvalidVoteWindow,
+        #[error("Vote has not started yet")]
+        VoteNotStarted,
+        #[error("Vote is closed")]
+        VoteClosed,
+        #[error("Vote must offer between 1 and MAX_OPTIONS options")]
+        InvalidOptionCount,
+        #[error("Option label is empty or longer than MAX_OPTION_LABEL_LEN bytes")]
+        InvalidOptionLabel,
+        #[error("Vote has no option at this index")]
+        InvalidOptionIndex,
+        #[error("Operation will overflow")]
+        Overflow,
+        #[error("Operation will underflow")]
+        Underflow,
+        #[error("Account is not of the expected type")]
+        InvalidAccountDiscriminator,
+        #[error("Account layout version is not supported")]
+        UnsupportedAccountVersion,
+        #[error("Account already uses the current layout")]
+        AlreadyMigrated,
+        #[error("Account must be writable")]
+        AccountNotWritable,
+        #[error("The same account was passed twice")]
+        DuplicateAccount,
+    }
+}
 
-impl From<CustomError> for ProgramError {
-    fn from(e: CustomError) -> Self {
-        ProgramError::Custom(e as u32)
+impl From<AccountHeaderError> for CustomError {
+    fn from(e: AccountHeaderError) -> Self {
+        match e {
+            AccountHeaderError::InvalidDiscriminator => CustomError::InvalidAccountDiscriminator,
+            AccountHeaderError::UnsupportedVersion => CustomError::UnsupportedAccountVersion,
+        }
     }
 }
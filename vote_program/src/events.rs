@@ -0,0 +1,75 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use program_common::Event;
+use solana_program::pubkey::Pubkey;
+
+/// NOTE:: Emitted by CreateVote
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteCreated {
+    pub vote: Pubkey,
+    pub owner: Pubkey,
+    pub start_date: i64,
+    pub end_date: i64,
+    /// NOTE:: Labels of the options, in index order
+    pub options: Vec<String>,
+}
+
+/// NOTE:: Emitted by AddVote
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteCast {
+    pub vote: Pubkey,
+    pub voter: Pubkey,
+    pub option: u8,
+    /// NOTE:: Tally of the option once the ballot is counted
+    pub votes: u64,
+}
+
+/// NOTE:: Emitted by RemoveVote
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteWithdrawn {
+    pub vote: Pubkey,
+    pub voter: Pubkey,
+    pub option: u8,
+    /// NOTE:: Tally of the option once the ballot is withdrawn
+    pub votes: u64,
+}
+
+/// NOTE:: Emitted by RemoveVote once the Vote has ended or been closed, the tally is left as is
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BallotReclaimed {
+    pub vote: Pubkey,
+    pub voter: Pubkey,
+    pub option: u8,
+}
+
+/// NOTE:: Emitted by CloseVote
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteClosed {
+    pub vote: Pubkey,
+    pub destination: Pubkey,
+    /// NOTE:: Rent sent to the destination
+    pub lamports: u64,
+}
+
+/// NOTE:: Emitted by Migrate
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteMigrated {
+    pub vote: Pubkey,
+    pub owner: Pubkey,
+    /// NOTE:: Tally carried over from the legacy score
+    pub votes: u64,
+}
+
+/// NOTE:: Every event logged by the program, the variant index leads the encoded data
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+#[borsh(use_discriminant = true)]
+#[repr(u8)]
+pub enum VoteEvent {
+    VoteCreated(VoteCreated) = 0,
+    VoteCast(VoteCast) = 1,
+    VoteWithdrawn(VoteWithdrawn) = 2,
+    VoteClosed(VoteClosed) = 3,
+    VoteMigrated(VoteMigrated) = 4,
+    BallotReclaimed(BallotReclaimed) = 5,
+}
+
+impl Event for VoteEvent {}
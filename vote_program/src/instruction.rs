@@ -0,0 +1,91 @@
+use crate::{instructions::VoteInstruction, state::find_ballot_address};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// Create `vote` owned by `owner`, both sign and `owner` pays for the account
+pub fn create_vote(
+    program_id: &Pubkey,
+    vote: &Pubkey,
+    owner: &Pubkey,
+    end_date: i64,
+    start_date: Option<i64>,
+    options: Vec<String>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VoteInstruction::CreateVote {
+            end_date,
+            start_date,
+            options,
+        },
+        vec![
+            AccountMeta::new(*vote, true),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Close `vote` and send its rent to `destination`, `owner` must sign
+pub fn close_vote(
+    program_id: &Pubkey,
+    vote: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VoteInstruction::CloseVote,
+        vec![
+            AccountMeta::new(*vote, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+    )
+}
+
+/// Cast the ballot of `voter` for `option`, `voter` pays for the ballot account
+pub fn add_vote(program_id: &Pubkey, vote: &Pubkey, voter: &Pubkey, option: u8) -> Instruction {
+    let (ballot, _) = find_ballot_address(program_id, vote, voter);
+    Instruction::new_with_borsh(
+        *program_id,
+        &VoteInstruction::AddVote { option },
+        vec![
+            AccountMeta::new(*vote, false),
+            AccountMeta::new(*voter, true),
+            AccountMeta::new(ballot, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Withdraw the ballot of `voter`, its rent goes back to `voter`. Also reclaims the rent of
+/// the ballot once `vote` has ended or been closed
+pub fn remove_vote(program_id: &Pubkey, vote: &Pubkey, voter: &Pubkey) -> Instruction {
+    let (ballot, _) = find_ballot_address(program_id, vote, voter);
+    Instruction::new_with_borsh(
+        *program_id,
+        &VoteInstruction::RemoveVote,
+        vec![
+            AccountMeta::new(*vote, false),
+            AccountMeta::new(*voter, true),
+            AccountMeta::new(ballot, false),
+        ],
+    )
+}
+
+/// Move the legacy `vote` to the current layout, `owner` pays for the extra rent
+pub fn migrate_vote(program_id: &Pubkey, vote: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VoteInstruction::Migrate,
+        vec![
+            AccountMeta::new(*vote, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
@@ -1,19 +1,31 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::program_error::ProgramError;
+use program_common::Unpack;
 
 #[derive(BorshSerialize, BorshDeserialize)]
 #[borsh(use_discriminant = true)]
 #[repr(u8)]
 pub enum VoteInstruction {
-    CreateVote = 0,
+    /// Votes are accepted from `start_date` (or creation time when `None`) up to `end_date`,
+    /// both unix timestamps. `options` holds between 1 and `MAX_OPTIONS` labels of at most
+    /// `MAX_OPTION_LABEL_LEN` bytes
+    CreateVote {
+        end_date: i64,
+        start_date: Option<i64>,
+        options: Vec<String>,
+    } = 0,
     CloseVote = 1,
-    AddVote = 2,
+    /// Cast a ballot for the option at index `option`. A ballot left over from a closed Vote
+    /// that had the same address is cast again
+    AddVote {
+        option: u8,
+    } = 2,
+    /// Withdraw the ballot of the voter and refund its rent. Once the Vote has ended or been
+    /// closed the tally is left as is and only the rent is refunded, as it is for a ballot of a
+    /// closed Vote that had the same address
     RemoveVote = 3,
+    /// Rewrite a Vote created with the legacy layout in the current one, the owner signs and
+    /// pays for the extra rent
+    Migrate = 4,
 }
 
-impl VoteInstruction {
-    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        BorshDeserialize::deserialize(&mut &input[..])
-            .map_err(|_| ProgramError::InvalidInstructionData)
-    }
-}
+impl Unpack for VoteInstruction {}
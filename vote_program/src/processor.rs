@@ -1,100 +1,309 @@
-use crate::state::VoteAccount;
-use borsh::{BorshDeserialize, BorshSerialize};
+use crate::{
+    accounts::{
+        AddVoteAccounts, CloseVoteAccounts, CreateVoteAccounts, MigrateVoteAccounts,
+        RemoveVoteAccounts,
+    },
+    error::CustomError,
+    events::{
+        BallotReclaimed, VoteCast, VoteClosed, VoteCreated, VoteEvent, VoteMigrated, VoteWithdrawn,
+    },
+    state::{
+        BallotAccount, LegacyVoteAccount, VoteAccount, VoteOption, BALLOT_SEED, MAX_OPTIONS,
+        MAX_OPTION_LABEL_LEN,
+    },
+};
+use borsh::BorshDeserialize;
+use program_common::{
+    check_key, close_account, create_account, resize_account, AccountSize, Event,
+};
 use solana_program::{
-    account_info::next_account_info, account_info::AccountInfo, entrypoint::ProgramResult, msg,
-    program::invoke, program_error::ProgramError, pubkey::Pubkey, rent::Rent, system_instruction,
-    system_program, sysvar::Sysvar,
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, pubkey::Pubkey,
+    sysvar::Sysvar,
 };
 
-pub fn process_create_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
+/// Fail unless the current cluster time falls within the voting window of `vote_data`
+fn check_vote_open(vote_data: &VoteAccount) -> ProgramResult {
+    let now = Clock::get()?.unix_timestamp;
+    if now < vote_data.start_date {
+        return Err(CustomError::VoteNotStarted.into());
+    }
+    if now > vote_data.end_date {
+        return Err(CustomError::VoteClosed.into());
+    }
+    Ok(())
+}
+
+pub fn process_create_vote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    end_date: i64,
+    start_date: Option<i64>,
+    options: Vec<String>,
+) -> ProgramResult {
+    let CreateVoteAccounts {
+        vote: vote_account,
+        payer: payer_account,
+        system_program,
+    } = CreateVoteAccounts::try_from(accounts)?;
 
-    let vote_account = next_account_info(accounts_iter)?;
-    let payer_account = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
-    let size_data = std::mem::size_of::<VoteAccount>();
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let start_date = start_date.unwrap_or(now);
+    if end_date <= start_date || end_date <= now {
+        return Err(CustomError::InvalidVoteWindow.into());
+    }
+
+    if options.is_empty() || options.len() > MAX_OPTIONS {
+        return Err(CustomError::InvalidOptionCount.into());
+    }
+    if options
+        .iter()
+        .any(|label| label.is_empty() || label.len() > MAX_OPTION_LABEL_LEN)
+    {
+        return Err(CustomError::InvalidOptionLabel.into());
+    }
+
+    let vote_data = VoteAccount::new(
+        *payer_account.key,
+        start_date,
+        end_date,
+        clock.slot,
+        options
+            .into_iter()
+            .map(|label| VoteOption { label, votes: 0 })
+            .collect(),
+    );
+
+    let size_data = vote_data.account_size();
     msg!("size data alloacted will be : {}", size_data);
 
-    let rent = Rent::get()?;
-    let required_lamports = rent.minimum_balance(size_data);
-    invoke(
-        &system_instruction::create_account(
-            payer_account.key,
-            vote_account.key,
-            required_lamports,
-            size_data as u64,
-            program_id,
-        ),
-        &[
-            payer_account.clone(),
-            vote_account.clone(),
-            system_program.clone(),
-        ],
+    create_account(
+        payer_account,
+        vote_account,
+        system_program,
+        program_id,
+        size_data,
+        &[],
     )?;
 
-    let vote_data = VoteAccount {
-        owner: *payer_account.key,
-        end_date: -1,
-        score: 0,
-    };
-
-    let mut account_data = &mut vote_account.data.borrow_mut()[..];
-    vote_data.serialize(&mut account_data)?;
+    vote_data.pack_into(&mut vote_account.data.borrow_mut())?;
     msg!("vote init to {:?}", vote_data);
-    Ok(())
+    VoteEvent::VoteCreated(VoteCreated {
+        vote: *vote_account.key,
+        owner: vote_data.owner,
+        start_date: vote_data.start_date,
+        end_date: vote_data.end_date,
+        options: vote_data
+            .options
+            .into_iter()
+            .map(|option| option.label)
+            .collect(),
+    })
+    .emit()
 }
 
 pub fn process_close_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let source_account = next_account_info(accounts_iter)?;
-    let dest_account = next_account_info(accounts_iter)?;
+    let CloseVoteAccounts {
+        vote: source_account,
+        destination: dest_account,
+        owner: owner_account,
+    } = CloseVoteAccounts::try_from(program_id, accounts)?;
 
-    if source_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-    let dest_lamports = dest_account.lamports();
-    **dest_account.lamports.borrow_mut() = dest_lamports
-        .checked_add(source_account.lamports())
-        .ok_or(ProgramError::InvalidInstructionData)?;
-    **source_account.lamports.borrow_mut() = 0;
-    source_account.assign(&system_program::ID);
-    source_account.realloc(0, false)?;
-    Ok(())
+    let vote_data = VoteAccount::unpack(&source_account.data.borrow())?;
+    check_key(owner_account, &vote_data.owner, CustomError::Unauthorized)?;
+    let lamports = source_account.lamports();
+    close_account(source_account, dest_account)?;
+    VoteEvent::VoteClosed(VoteClosed {
+        vote: *source_account.key,
+        destination: *dest_account.key,
+        lamports,
+    })
+    .emit()
 }
 
 pub fn process_remove_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let vote_account = next_account_info(accounts_iter)?;
+    let RemoveVoteAccounts {
+        vote: vote_account,
+        voter: voter_account,
+        ballot: ballot_account,
+    } = RemoveVoteAccounts::try_from(program_id, accounts)?;
 
-    if vote_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
+    let ballot_data = BallotAccount::unpack(&ballot_account.data.borrow())?;
+    if ballot_data.vote != *vote_account.key || ballot_data.voter != *voter_account.key {
+        return Err(CustomError::InvalidBallotAccount.into());
     }
 
-    let mut data = vote_account.data.borrow_mut();
-    let mut vote_data = VoteAccount::try_from_slice(&data)?;
+    // NOTE:: once the Vote has ended or been closed its tally is final, the ballot only gets its
+    // NOTE:: rent back, as does a ballot of a closed Vote created again at the same address
+    let votes = if vote_account.owner != program_id {
+        None
+    } else {
+        let mut data = vote_account.data.borrow_mut();
+        let mut vote_data = VoteAccount::unpack(&data)?;
+        if ballot_data.vote_created_slot != vote_data.created_slot
+            || Clock::get()?.unix_timestamp > vote_data.end_date
+        {
+            None
+        } else {
+            check_vote_open(&vote_data)?;
 
-    vote_data.score -= 1;
-    vote_data.serialize(&mut &mut data[..])?;
-    msg!("one vote removed");
-    Ok(())
+            let vote_option = vote_data
+                .options
+                .get_mut(ballot_data.option as usize)
+                .ok_or(CustomError::InvalidOptionIndex)?;
+            vote_option.votes = vote_option
+                .votes
+                .checked_sub(1)
+                .ok_or(CustomError::Underflow)?;
+            let votes = vote_option.votes;
+            vote_data.pack_into(&mut data)?;
+            Some(votes)
+        }
+    };
+
+    close_account(ballot_account, voter_account)?;
+    match votes {
+        Some(votes) => {
+            msg!("one vote removed");
+            VoteEvent::VoteWithdrawn(VoteWithdrawn {
+                vote: *vote_account.key,
+                voter: *voter_account.key,
+                option: ballot_data.option,
+                votes,
+            })
+            .emit()
+        }
+        None => {
+            msg!("ballot reclaimed, the vote is over");
+            VoteEvent::BallotReclaimed(BallotReclaimed {
+                vote: *vote_account.key,
+                voter: *voter_account.key,
+                option: ballot_data.option,
+            })
+            .emit()
+        }
+    }
 }
 
-pub fn process_add_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+pub fn process_add_vote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    option: u8,
+) -> ProgramResult {
     msg!("process_add_vote called");
-    let accounts_iter = &mut accounts.iter();
-    let vote_account = next_account_info(accounts_iter)?;
+    let AddVoteAccounts {
+        vote: vote_account,
+        voter: voter_account,
+        ballot: ballot_account,
+        system_program,
+        ballot_bump: bump,
+    } = AddVoteAccounts::try_from(program_id, accounts)?;
+
+    let mut vote_data = VoteAccount::unpack(&vote_account.data.borrow())?;
+    let ballot_data = BallotAccount::new(
+        *vote_account.key,
+        vote_data.created_slot,
+        *voter_account.key,
+        option,
+        bump,
+    );
+    if ballot_account.owner == program_id {
+        // a ballot left over from a closed Vote that had the same address is cast again
+        let previous = BallotAccount::unpack(&ballot_account.data.borrow())?;
+        if previous.vote_created_slot == vote_data.created_slot {
+            return Err(CustomError::AlreadyVoted.into());
+        }
+    }
 
-    if vote_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
+    check_vote_open(&vote_data)?;
+    if option as usize >= vote_data.options.len() {
+        return Err(CustomError::InvalidOptionIndex.into());
     }
 
-    let mut data = vote_account.data.borrow_mut();
-    let mut vote_data = VoteAccount::try_from_slice(&data)?;
-    msg!("old score = {}", vote_data.score);
+    if ballot_account.owner != program_id {
+        create_account(
+            voter_account,
+            ballot_account,
+            system_program,
+            program_id,
+            ballot_data.account_size(),
+            &[&[
+                BALLOT_SEED,
+                vote_account.key.as_ref(),
+                voter_account.key.as_ref(),
+                &[bump],
+            ]],
+        )?;
+    }
 
-    vote_data.score += 1;
-    msg!("new score = {}", vote_data.score);
-    vote_data.serialize(&mut &mut data[..])?;
-    msg!("one vote added, for a total: {}", vote_data.score);
-    Ok(())
+    ballot_data.pack_into(&mut ballot_account.data.borrow_mut())?;
+
+    let vote_option = &mut vote_data.options[option as usize];
+    vote_option.votes = vote_option
+        .votes
+        .checked_add(1)
+        .ok_or(CustomError::Overflow)?;
+    msg!(
+        "one vote added to {}, for a total: {}",
+        vote_option.label,
+        vote_option.votes
+    );
+    let votes = vote_option.votes;
+    vote_data.pack_into(&mut vote_account.data.borrow_mut())?;
+    VoteEvent::VoteCast(VoteCast {
+        vote: *vote_account.key,
+        voter: *voter_account.key,
+        option,
+        votes,
+    })
+    .emit()
+}
+
+pub fn process_migrate_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let MigrateVoteAccounts {
+        vote: vote_account,
+        owner: owner_account,
+        system_program,
+    } = MigrateVoteAccounts::try_from(program_id, accounts)?;
+    if vote_account.data_len() != LegacyVoteAccount::LEN {
+        // anything else must already be a current account
+        VoteAccount::unpack(&vote_account.data.borrow())?;
+        return Err(CustomError::AlreadyMigrated.into());
+    }
+
+    let legacy_data = LegacyVoteAccount::try_from_slice(&vote_account.data.borrow())?;
+    check_key(owner_account, &legacy_data.owner, CustomError::Unauthorized)?;
+
+    // legacy Votes had no start date, were always written with an end date of -1 that was never
+    // enforced, and could be driven below zero by RemoveVote
+    let end_date = match legacy_data.end_date {
+        end_date if end_date < 0 => i64::MAX,
+        end_date => end_date,
+    };
+    let vote_data = VoteAccount::new(
+        legacy_data.owner,
+        i64::MIN,
+        end_date,
+        Clock::get()?.slot,
+        vec![VoteOption {
+            label: LegacyVoteAccount::OPTION_LABEL.to_string(),
+            votes: legacy_data.score.max(0) as u64,
+        }],
+    );
+
+    resize_account(
+        vote_account,
+        owner_account,
+        system_program,
+        vote_data.account_size(),
+    )?;
+
+    vote_data.pack_into(&mut vote_account.data.borrow_mut())?;
+    msg!("vote migrated to {:?}", vote_data);
+    VoteEvent::VoteMigrated(VoteMigrated {
+        vote: *vote_account.key,
+        owner: vote_data.owner,
+        votes: vote_data.options[0].votes,
+    })
+    .emit()
 }
@@ -1,14 +1,175 @@
+use crate::error::CustomError;
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use program_common::{check_header, AccountSize, ACCOUNT_HEADER_LEN};
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+/// NOTE:: Seed prefix of the ballot PDA, derived from [BALLOT_SEED, vote, voter]
+pub const BALLOT_SEED: &[u8] = b"ballot";
+/// NOTE:: Maximum number of options a Vote can offer
+pub const MAX_OPTIONS: usize = 8;
+/// NOTE:: Maximum length in bytes of an option label
+pub const MAX_OPTION_LABEL_LEN: usize = 32;
+/// NOTE:: Layout version written by this program in every account it creates
+pub const ACCOUNT_VERSION: u8 = 1;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct VoteOption {
+    /// NOTE:: Label shown to the voters
+    /// NOTE:: 4 + label length bytes
+    pub label: String,
+    /// NOTE:: Number of ballots cast for this option
+    /// NOTE:: 8 bytes
+    pub votes: u64,
+}
+
+impl VoteOption {
+    /// Serialized size of the option, a length-prefixed label and its tally
+    pub fn serialized_len(&self) -> usize {
+        4 + self.label.len() + 8
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct VoteAccount {
+    /// NOTE:: Always VoteAccount::DISCRIMINATOR
+    /// NOTE:: 8 bytes
+    pub discriminator: [u8; 8],
+    /// NOTE:: Layout version of the account
+    /// NOTE:: 1 byte
+    pub version: u8,
     /// NOTE:: Owner of the Vote, only him can close the Vote
     /// NOTE:: 32 (1 * 32) bytes
     pub owner: Pubkey,
-    /// NOTE:: Represent the end timestamp
+    /// NOTE:: Represent the start timestamp, no vote is accepted before it
+    /// NOTE:: 8 bytes
+    pub start_date: i64,
+    /// NOTE:: Represent the end timestamp, no vote is accepted after it
+    /// NOTE:: 8 bytes
+    pub end_date: i64,
+    /// NOTE:: Slot the Vote was created in, ballots carry it to tell a Vote from another one
+    /// NOTE:: created later at the same address, once this one is closed
+    /// NOTE:: 8 bytes
+    pub created_slot: u64,
+    /// NOTE:: Options of the Vote with their tallies
+    /// NOTE:: 4 + sum of each option size bytes
+    pub options: Vec<VoteOption>,
+}
+
+impl VoteAccount {
+    pub const DISCRIMINATOR: [u8; 8] = *b"vote\0\0\0\0";
+    /// NOTE:: Offset of `owner` in the account data, right after the header, to filter the
+    /// NOTE:: Votes of an owner with getProgramAccounts
+    pub const OWNER_OFFSET: usize = ACCOUNT_HEADER_LEN;
+
+    pub fn new(
+        owner: Pubkey,
+        start_date: i64,
+        end_date: i64,
+        created_slot: u64,
+        options: Vec<VoteOption>,
+    ) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            owner,
+            start_date,
+            end_date,
+            created_slot,
+            options,
+        }
+    }
+
+    /// Deserialize a Vote, refusing any other account type or layout version
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        check_header(data, &Self::DISCRIMINATOR, ACCOUNT_VERSION).map_err(CustomError::from)?;
+        Ok(Self::try_from_slice(data)?)
+    }
+}
+
+impl AccountSize for VoteAccount {
+    fn account_size(&self) -> usize {
+        let options_size: usize = self.options.iter().map(VoteOption::serialized_len).sum();
+        ACCOUNT_HEADER_LEN + PUBKEY_BYTES + 8 + 8 + 8 + 4 + options_size
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct BallotAccount {
+    /// NOTE:: Always BallotAccount::DISCRIMINATOR
+    /// NOTE:: 8 bytes
+    pub discriminator: [u8; 8],
+    /// NOTE:: Layout version of the account
+    /// NOTE:: 1 byte
+    pub version: u8,
+    /// NOTE:: Vote this ballot was cast on
+    /// NOTE:: 32 bytes
+    pub vote: Pubkey,
+    /// NOTE:: `created_slot` of the Vote this ballot was cast on
+    /// NOTE:: 8 bytes
+    pub vote_created_slot: u64,
+    /// NOTE:: Wallet that cast the ballot, the rent goes back to him on RemoveVote
+    /// NOTE:: 32 bytes
+    pub voter: Pubkey,
+    /// NOTE:: Index of the option the ballot was cast for
+    /// NOTE:: 1 byte
+    pub option: u8,
+    /// NOTE:: Bump seed of the ballot PDA
+    /// NOTE:: 1 byte
+    pub bump: u8,
+}
+
+impl BallotAccount {
+    pub const DISCRIMINATOR: [u8; 8] = *b"ballot\0\0";
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + PUBKEY_BYTES + 8 + PUBKEY_BYTES + 1 + 1;
+
+    pub fn new(vote: Pubkey, vote_created_slot: u64, voter: Pubkey, option: u8, bump: u8) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            vote,
+            vote_created_slot,
+            voter,
+            option,
+            bump,
+        }
+    }
+
+    /// Deserialize a ballot, refusing any other account type or layout version
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        check_header(data, &Self::DISCRIMINATOR, ACCOUNT_VERSION).map_err(CustomError::from)?;
+        Ok(Self::try_from_slice(data)?)
+    }
+}
+
+impl AccountSize for BallotAccount {
+    fn account_size(&self) -> usize {
+        Self::LEN
+    }
+}
+
+/// NOTE:: Layout of the Votes created before accounts carried a discriminator, only read by
+/// NOTE:: Migrate
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LegacyVoteAccount {
+    /// NOTE:: 32 bytes
+    pub owner: Pubkey,
+    /// NOTE:: Always written as -1 and never enforced, migrated as no end date
     /// NOTE:: 8 bytes
     pub end_date: i64,
-    ///NOTE:: Reprensent the score of the Vote
+    /// NOTE:: Net number of votes, a single tally for the whole Vote
+    /// NOTE:: 8 bytes
     pub score: i64,
 }
+
+impl LegacyVoteAccount {
+    pub const LEN: usize = PUBKEY_BYTES + 8 + 8;
+    /// NOTE:: Label of the single option holding the legacy score after migration
+    pub const OPTION_LABEL: &'static str = "score";
+}
+
+pub fn find_ballot_address(program_id: &Pubkey, vote: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BALLOT_SEED, vote.as_ref(), voter.as_ref()], program_id)
+}
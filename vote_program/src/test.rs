@@ -1,16 +1,26 @@
 use borsh::BorshDeserialize;
-use env_logger;
+use error::CustomError;
+use events::{BallotReclaimed, VoteCast, VoteClosed, VoteCreated, VoteEvent, VoteWithdrawn};
 use log::info;
+use program_common::{stubs, AccountSize, ErrorCode, Event};
 use solana_program_test::*;
 use solana_sdk::{
+    account::Account,
+    clock::Clock,
     commitment_config::CommitmentLevel,
-    instruction::{AccountMeta, Instruction},
+    hash::Hash,
+    instruction::{AccountMeta, InstructionError},
+    program_error::ProgramError,
+    rent::Rent,
     signature::Keypair,
     signer::Signer,
-    system_program,
-    transaction::Transaction,
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use state::{
+    find_ballot_address, BallotAccount, LegacyVoteAccount, VoteAccount, VoteOption,
+    ACCOUNT_VERSION, MAX_OPTIONS, MAX_OPTION_LABEL_LEN,
 };
-use state::VoteAccount;
 
 use super::*;
 
@@ -24,6 +34,20 @@ fn setup() {
         )
         .filter_module("vote_program::test", log::LevelFilter::Trace)
         .try_init();
+
+    stubs::log_data_to_transaction_logs();
+}
+
+async fn fund(
+    bank_clients: &mut BanksClient,
+    payer: &Keypair,
+    to: &Pubkey,
+    recent_blockhash: Hash,
+) {
+    let fund_instruction = system_instruction::transfer(&payer.pubkey(), to, 1_000_000_000);
+    let mut tx = Transaction::new_with_payer(&[fund_instruction], Some(&payer.pubkey()));
+    tx.sign(&[payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
 }
 
 #[tokio::test]
@@ -40,14 +64,13 @@ async fn test_vote_lifecycle() {
     let vote_keypair = Keypair::new();
     info!("Testing vote account creation...");
 
-    let init_instruction = Instruction::new_with_bytes(
-        program_id,
-        &[VoteInstruction::CreateVote as u8],
-        vec![
-            AccountMeta::new(vote_keypair.pubkey(), true),
-            AccountMeta::new(payer.pubkey(), true),
-            AccountMeta::new_readonly(system_program::id(), false),
-        ],
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
     );
 
     let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
@@ -62,19 +85,18 @@ async fn test_vote_lifecycle() {
         .unwrap();
     let vote = VoteAccount::try_from_slice(&account.data).unwrap();
     assert_eq!(vote.owner, payer.pubkey());
-    assert_eq!(vote.score, 0);
+    assert_eq!(vote.end_date, i64::MAX);
+    assert_eq!(vote.options[0].votes, 0);
     info!("Vote account created successfully: {:?}", vote);
 
     // Test adding votes
     info!("Testing vote addition...");
-    let add_vote_instruction = Instruction::new_with_bytes(
-        program_id,
-        &[VoteInstruction::AddVote as u8],
-        vec![AccountMeta::new(vote_keypair.pubkey(), true)],
-    );
+    let (ballot, _) = find_ballot_address(&program_id, &vote_keypair.pubkey(), &payer.pubkey());
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &payer.pubkey(), 0);
 
     let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
-    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    tx.sign(&[&payer], recent_blockhash);
     bank_clients.process_transaction(tx).await.unwrap();
 
     // Verify vote was added
@@ -84,21 +106,30 @@ async fn test_vote_lifecycle() {
         .unwrap()
         .unwrap();
     let vote = VoteAccount::try_from_slice(&account.data).unwrap();
-    assert_eq!(vote.score, 1);
-    info!("Vote added successfully, new score: {}", vote.score);
+    assert_eq!(vote.options[0].votes, 1);
+    info!(
+        "Vote added successfully, new score: {}",
+        vote.options[0].votes
+    );
+
+    let account = bank_clients.get_account(ballot).await.unwrap().unwrap();
+    let ballot_data = BallotAccount::try_from_slice(&account.data).unwrap();
+    assert_eq!(ballot_data.vote, vote_keypair.pubkey());
+    assert_eq!(ballot_data.voter, payer.pubkey());
+    info!("Ballot recorded: {:?}", ballot_data);
 
     // Test removing votes
     info!("Testing vote removal...");
-    let remove_vote_instruction = Instruction::new_with_bytes(
-        program_id,
-        &[VoteInstruction::RemoveVote as u8],
-        vec![AccountMeta::new(vote_keypair.pubkey(), true)],
-    );
+    let remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &payer.pubkey());
 
     let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
-    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    tx.sign(&[&payer], recent_blockhash);
     bank_clients.process_transaction(tx).await.unwrap();
 
+    let account = bank_clients.get_account(ballot).await.unwrap();
+    assert!(account.is_none(), "Ballot account should be closed");
+
     // Verify vote was removed
     let account = bank_clients
         .get_account(vote_keypair.pubkey())
@@ -106,22 +137,23 @@ async fn test_vote_lifecycle() {
         .unwrap()
         .unwrap();
     let vote = VoteAccount::try_from_slice(&account.data).unwrap();
-    assert_eq!(vote.score, 0);
-    info!("Vote removed successfully, new score: {}", vote.score);
+    assert_eq!(vote.options[0].votes, 0);
+    info!(
+        "Vote removed successfully, new score: {}",
+        vote.options[0].votes
+    );
 
     // Test closing vote account
     info!("Testing vote account closure...");
-    let close_instruction = Instruction::new_with_bytes(
-        program_id,
-        &[VoteInstruction::CloseVote as u8],
-        vec![
-            AccountMeta::new(vote_keypair.pubkey(), true),
-            AccountMeta::new(payer.pubkey(), true),
-        ],
+    let close_instruction = instruction::close_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        &payer.pubkey(),
     );
 
     let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
-    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    tx.sign(&[&payer], recent_blockhash);
     bank_clients.process_transaction(tx).await.unwrap();
 
     // Verify account was closed
@@ -146,14 +178,13 @@ async fn test_multiple_votes() {
     let vote_keypair = Keypair::new();
 
     // Create vote account
-    let init_instruction = Instruction::new_with_bytes(
-        program_id,
-        &[VoteInstruction::CreateVote as u8],
-        vec![
-            AccountMeta::new(vote_keypair.pubkey(), true),
-            AccountMeta::new(payer.pubkey(), true),
-            AccountMeta::new_readonly(system_program::id(), false),
-        ],
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
     );
     {
         let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
@@ -165,19 +196,21 @@ async fn test_multiple_votes() {
         info!("vote account created");
     }
 
+    let voters: Vec<Keypair> = (0..5).map(|_| Keypair::new()).collect();
+    for voter in &voters {
+        fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+    }
+
     // Add multiple votes
-    for i in 0..5 {
+    for (i, voter) in voters.iter().enumerate() {
         info!("Trying to add vote, i  = {}", i);
-        let add_vote_instruction = Instruction::new_with_bytes(
-            program_id,
-            &[VoteInstruction::AddVote as u8],
-            vec![AccountMeta::new(vote_keypair.pubkey(), true)],
-        );
+        let add_vote_instruction =
+            instruction::add_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey(), 0);
         info!("instruction created");
 
         let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
         info!("tx created");
-        tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+        tx.sign(&[&payer, voter], recent_blockhash);
         info!("tx signed");
         bank_clients.process_transaction(tx).await.unwrap();
         info!("tx processed");
@@ -188,21 +221,22 @@ async fn test_multiple_votes() {
             .unwrap()
             .unwrap();
         let vote = VoteAccount::try_from_slice(&account.data).unwrap();
-        assert_eq!(vote.score, i + 1);
-        info!("Added vote #{}, total score: {}", i + 1, vote.score);
+        assert_eq!(vote.options[0].votes, i as u64 + 1);
+        info!(
+            "Added vote #{}, total score: {}",
+            i + 1,
+            vote.options[0].votes
+        );
     }
 
     // Remove some votes
-    for i in 0..3 {
+    for (i, voter) in voters.iter().take(3).enumerate() {
         info!("trying to remove vote");
-        let remove_vote_instruction = Instruction::new_with_bytes(
-            program_id,
-            &[VoteInstruction::RemoveVote as u8],
-            vec![AccountMeta::new(vote_keypair.pubkey(), true)],
-        );
+        let remove_vote_instruction =
+            instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey());
 
         let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
-        tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+        tx.sign(&[&payer, voter], recent_blockhash);
         bank_clients.process_transaction(tx).await.unwrap();
 
         let account = bank_clients
@@ -211,22 +245,24 @@ async fn test_multiple_votes() {
             .unwrap()
             .unwrap();
         let vote = VoteAccount::try_from_slice(&account.data).unwrap();
-        assert_eq!(vote.score, 4 - i);
-        info!("Removed vote #{}, total score: {}", i + 1, vote.score);
+        assert_eq!(vote.options[0].votes, 4 - i as u64);
+        info!(
+            "Removed vote #{}, total score: {}",
+            i + 1,
+            vote.options[0].votes
+        );
     }
 
     info!("Closing vote account...");
-    let close_instruction = Instruction::new_with_bytes(
-        program_id,
-        &[VoteInstruction::CloseVote as u8],
-        vec![
-            AccountMeta::new(vote_keypair.pubkey(), true),
-            AccountMeta::new(payer.pubkey(), true),
-        ],
+    let close_instruction = instruction::close_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        &payer.pubkey(),
     );
 
     let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
-    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    tx.sign(&[&payer], recent_blockhash);
     bank_clients.process_transaction(tx).await.unwrap();
 
     // Verify account was closed
@@ -237,3 +273,1358 @@ async fn test_multiple_votes() {
     assert!(account.is_none(), "Vote account should be closed");
     info!("Vote account closed successfully");
 }
+
+#[tokio::test]
+async fn test_close_vote_requires_owner() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    let vote_keypair = Keypair::new();
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
+    );
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let attacker = Keypair::new();
+    fund(
+        &mut bank_clients,
+        &payer,
+        &attacker.pubkey(),
+        recent_blockhash,
+    )
+    .await;
+
+    // the owner is named but did not sign
+    info!("Testing vote closure without the owner signature...");
+    let mut close_instruction = instruction::close_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &attacker.pubkey(),
+        &payer.pubkey(),
+    );
+    close_instruction.accounts[2].is_signer = false;
+    let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&attacker.pubkey()));
+    tx.sign(&[&attacker], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::MissingOwnerSignature as u32)
+        )
+    );
+
+    // someone else signs in place of the owner
+    info!("Testing vote closure signed by another wallet...");
+    let close_instruction = instruction::close_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &attacker.pubkey(),
+        &attacker.pubkey(),
+    );
+    let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&attacker.pubkey()));
+    tx.sign(&[&attacker], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::Unauthorized as u32)
+        )
+    );
+
+    // the owner can't send the lamports back into the Vote itself
+    info!("Testing vote closure into the vote account...");
+    let close_instruction = instruction::close_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+    );
+    let mut tx = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::DuplicateAccount as u32)
+        )
+    );
+
+    let account = bank_clients
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap();
+    assert!(account.is_some(), "Vote account should still be open");
+    info!("Vote account left untouched");
+}
+
+#[tokio::test]
+async fn test_single_ballot_per_voter() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    let vote_keypair = Keypair::new();
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
+    );
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let voter = Keypair::new();
+    fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+    let (ballot, _) = find_ballot_address(&program_id, &vote_keypair.pubkey(), &voter.pubkey());
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey(), 0);
+    let remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey());
+
+    // removing a ballot that was never cast
+    info!("Testing vote removal without a ballot...");
+    let mut tx = Transaction::new_with_payer(
+        std::slice::from_ref(&remove_vote_instruction),
+        Some(&voter.pubkey()),
+    );
+    tx.sign(&[&voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::BallotNotFound as u32)
+        )
+    );
+
+    // a second ballot from the same voter in the same transaction
+    info!("Testing double vote...");
+    let mut tx = Transaction::new_with_payer(
+        &[add_vote_instruction.clone(), add_vote_instruction.clone()],
+        Some(&voter.pubkey()),
+    );
+    tx.sign(&[&voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(CustomError::AlreadyVoted as u32)
+        )
+    );
+
+    // a second ballot from the same voter in a later transaction
+    let mut tx = Transaction::new_with_payer(
+        std::slice::from_ref(&add_vote_instruction),
+        Some(&voter.pubkey()),
+    );
+    tx.sign(&[&voter], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+    let balance_after_vote = bank_clients.get_balance(voter.pubkey()).await.unwrap();
+
+    let mut tx = Transaction::new_with_payer(
+        std::slice::from_ref(&add_vote_instruction),
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::AlreadyVoted as u32)
+        )
+    );
+
+    let account = bank_clients
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::try_from_slice(&account.data).unwrap();
+    assert_eq!(vote.options[0].votes, 1);
+
+    // removing the ballot refunds its rent to the voter
+    info!("Testing ballot rent refund...");
+    let ballot_rent = bank_clients.get_balance(ballot).await.unwrap();
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    assert_eq!(
+        bank_clients.get_balance(voter.pubkey()).await.unwrap(),
+        balance_after_vote + ballot_rent
+    );
+    let account = bank_clients.get_account(ballot).await.unwrap();
+    assert!(account.is_none(), "Ballot account should be closed");
+}
+
+#[tokio::test]
+async fn test_prefunded_ballot() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    let vote_keypair = Keypair::new();
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
+    );
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    // anyone can derive the ballot of a voter and send it lamports before the voter votes
+    info!("Testing vote on a funded ballot address...");
+    let voter = Keypair::new();
+    fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+    let (ballot, _) = find_ballot_address(&program_id, &vote_keypair.pubkey(), &voter.pubkey());
+    let rent = bank_clients.get_rent().await.unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &ballot,
+            rent.minimum_balance(0),
+        )],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey(), 1);
+    let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&voter.pubkey()));
+    tx.sign(&[&voter], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let account = bank_clients.get_account(ballot).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id);
+    assert_eq!(account.lamports, rent.minimum_balance(account.data.len()));
+    let ballot_data = BallotAccount::unpack(&account.data).unwrap();
+    assert_eq!(ballot_data.voter, voter.pubkey());
+    assert_eq!(ballot_data.option, 1);
+
+    let account = bank_clients
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::unpack(&account.data).unwrap();
+    assert_eq!(vote.options[1].votes, 1);
+}
+
+async fn set_unix_timestamp(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_vote_window() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+        .start_with_context()
+        .await;
+    let payer = context.payer.insecure_clone();
+
+    let now = 1_700_000_000;
+    let start_date = now + 100;
+    let end_date = now + 1_000;
+    set_unix_timestamp(&mut context, now).await;
+
+    // the window must end in the future and after its start
+    for (end_date, start_date) in [(now - 1, None), (start_date, Some(start_date + 1))] {
+        let vote_keypair = Keypair::new();
+        let init_instruction = instruction::create_vote(
+            &program_id,
+            &vote_keypair.pubkey(),
+            &payer.pubkey(),
+            end_date,
+            start_date,
+            vec!["yes".to_string(), "no".to_string()],
+        );
+        let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &vote_keypair], context.last_blockhash);
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(CustomError::InvalidVoteWindow as u32)
+            )
+        );
+    }
+
+    let vote_keypair = Keypair::new();
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        end_date,
+        Some(start_date),
+        vec!["yes".to_string(), "no".to_string()],
+    );
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], context.last_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::try_from_slice(&account.data).unwrap();
+    assert_eq!(vote.start_date, start_date);
+    assert_eq!(vote.end_date, end_date);
+
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &payer.pubkey(), 0);
+    let remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &payer.pubkey());
+
+    // before the start date
+    info!("Testing vote before the start date...");
+    let mut tx = Transaction::new_with_payer(
+        std::slice::from_ref(&add_vote_instruction),
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], context.last_blockhash);
+    let err = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::VoteNotStarted as u32)
+        )
+    );
+
+    // within the window, up to the end date included
+    info!("Testing vote within the window...");
+    set_unix_timestamp(&mut context, end_date).await;
+    // same transaction as the rejected one, it needs a fresh blockhash to be processed again
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut tx = Transaction::new_with_payer(
+        std::slice::from_ref(&add_vote_instruction),
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], recent_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // after the deadline no ballot is accepted
+    info!("Testing vote after the end date...");
+    set_unix_timestamp(&mut context, end_date + 1).await;
+    let voter = Keypair::new();
+    fund(
+        &mut context.banks_client,
+        &payer,
+        &voter.pubkey(),
+        context.last_blockhash,
+    )
+    .await;
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey(), 0);
+    let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voter], context.last_blockhash);
+    let err = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::VoteClosed as u32)
+        )
+    );
+
+    // a ballot withdrawn after the deadline gets its rent back, the final tally is kept
+    info!("Testing ballot reclaim after the end date...");
+    let (ballot, _) = find_ballot_address(&program_id, &vote_keypair.pubkey(), &payer.pubkey());
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer], context.last_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let account = context.banks_client.get_account(ballot).await.unwrap();
+    assert!(account.is_none(), "Ballot account should be closed");
+
+    let account = context
+        .banks_client
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::try_from_slice(&account.data).unwrap();
+    assert_eq!(vote.options[0].votes, 1);
+}
+
+#[tokio::test]
+async fn test_reclaim_ballot_of_closed_vote() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    let vote_keypair = Keypair::new();
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
+    );
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let voter = Keypair::new();
+    fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+    let (ballot, _) = find_ballot_address(&program_id, &vote_keypair.pubkey(), &voter.pubkey());
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey(), 0);
+    let close_instruction = instruction::close_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        &payer.pubkey(),
+    );
+    let mut tx = Transaction::new_with_payer(
+        &[add_vote_instruction, close_instruction],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+    assert!(bank_clients
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .is_none());
+
+    // the ballot of another voter can't be reclaimed in its place
+    let other_voter = Keypair::new();
+    fund(
+        &mut bank_clients,
+        &payer,
+        &other_voter.pubkey(),
+        recent_blockhash,
+    )
+    .await;
+    let mut remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &other_voter.pubkey());
+    remove_vote_instruction.accounts[2] = AccountMeta::new(ballot, false);
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &other_voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::InvalidBallotAccount as u32)
+        )
+    );
+
+    // the Vote is gone, its ballots still give their rent back to their voters
+    info!("Testing ballot reclaim after CloseVote...");
+    let ballot_rent = bank_clients.get_balance(ballot).await.unwrap();
+    let balance_before = bank_clients.get_balance(voter.pubkey()).await.unwrap();
+    let remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &voter.pubkey());
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    assert_eq!(
+        bank_clients.get_balance(voter.pubkey()).await.unwrap(),
+        balance_before + ballot_rent
+    );
+    let account = bank_clients.get_account(ballot).await.unwrap();
+    assert!(account.is_none(), "Ballot account should be closed");
+}
+
+#[tokio::test]
+async fn test_ballots_of_recreated_vote() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+        .start_with_context()
+        .await;
+    let payer = context.payer.insecure_clone();
+
+    let vote_keypair = Keypair::new();
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
+    );
+    let voters = [Keypair::new(), Keypair::new()];
+    let mut instructions = vec![init_instruction.clone()];
+    for voter in &voters {
+        instructions.push(system_instruction::transfer(
+            &payer.pubkey(),
+            &voter.pubkey(),
+            1_000_000_000,
+        ));
+        instructions.push(instruction::add_vote(
+            &program_id,
+            &vote_keypair.pubkey(),
+            &voter.pubkey(),
+            0,
+        ));
+    }
+    instructions.push(instruction::close_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        &payer.pubkey(),
+    ));
+    let mut tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    tx.sign(
+        &[&payer, &vote_keypair, &voters[0], &voters[1]],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // a new Vote at the same address, in a later slot
+    info!("Testing the ballots of a closed Vote created again...");
+    let slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(slot + 2).unwrap();
+    let recent_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // a ballot of the closed Vote is cast again on the new one, only once
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &voters[1].pubkey(), 1);
+    let mut tx = Transaction::new_with_payer(
+        std::slice::from_ref(&add_vote_instruction),
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer, &voters[1]], recent_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[
+            add_vote_instruction,
+            system_instruction::transfer(&payer.pubkey(), &voters[1].pubkey(), 1),
+        ],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer, &voters[1]], recent_blockhash);
+    let err = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::AlreadyVoted as u32)
+        )
+    );
+
+    // the other one only gives its rent back, the tally of the new Vote is left as is
+    let (ballot, _) = find_ballot_address(&program_id, &vote_keypair.pubkey(), &voters[0].pubkey());
+    let ballot_rent = context.banks_client.get_balance(ballot).await.unwrap();
+    let balance_before = context
+        .banks_client
+        .get_balance(voters[0].pubkey())
+        .await
+        .unwrap();
+    let remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &voters[0].pubkey());
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voters[0]], recent_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(voters[0].pubkey())
+            .await
+            .unwrap(),
+        balance_before + ballot_rent
+    );
+    let account = context.banks_client.get_account(ballot).await.unwrap();
+    assert!(account.is_none(), "Ballot account should be closed");
+    let account = context
+        .banks_client
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::unpack(&account.data).unwrap();
+    assert_eq!(vote.options[0].votes, 0);
+    assert_eq!(vote.options[1].votes, 1);
+
+    // the ballot cast again is withdrawn from the new Vote
+    let remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &voters[1].pubkey());
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voters[1]], recent_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let account = context
+        .banks_client
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::unpack(&account.data).unwrap();
+    assert_eq!(vote.options[1].votes, 0);
+}
+
+#[tokio::test]
+async fn test_poll_options() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+
+    // option lists out of bounds are rejected
+    for (options, error) in [
+        (vec![], CustomError::InvalidOptionCount),
+        (
+            (0..=MAX_OPTIONS).map(|i| format!("option {}", i)).collect(),
+            CustomError::InvalidOptionCount,
+        ),
+        (
+            vec!["yes".to_string(), String::new()],
+            CustomError::InvalidOptionLabel,
+        ),
+        (
+            vec!["x".repeat(MAX_OPTION_LABEL_LEN + 1)],
+            CustomError::InvalidOptionLabel,
+        ),
+    ] {
+        let vote_keypair = Keypair::new();
+        let init_instruction = instruction::create_vote(
+            &program_id,
+            &vote_keypair.pubkey(),
+            &payer.pubkey(),
+            i64::MAX,
+            None,
+            options,
+        );
+        let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+        let err = bank_clients.process_transaction(tx).await.unwrap_err();
+        assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+
+    let labels = vec![
+        "red".to_string(),
+        "green".to_string(),
+        "x".repeat(MAX_OPTION_LABEL_LEN),
+    ];
+    let vote_keypair = Keypair::new();
+    let init_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &payer.pubkey(),
+        i64::MAX,
+        None,
+        labels.clone(),
+    );
+    let mut tx = Transaction::new_with_payer(&[init_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let account = bank_clients
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::try_from_slice(&account.data).unwrap();
+    assert_eq!(account.data.len(), vote.account_size());
+    let vote_labels: Vec<String> = vote.options.iter().map(|o| o.label.clone()).collect();
+    assert_eq!(vote_labels, labels);
+    info!("Poll created: {:?}", vote);
+
+    // voter i casts a ballot for option i
+    let voters: Vec<Keypair> = (0..labels.len()).map(|_| Keypair::new()).collect();
+    for (i, voter) in voters.iter().enumerate() {
+        fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+        let (ballot, _) = find_ballot_address(&program_id, &vote_keypair.pubkey(), &voter.pubkey());
+        let add_vote_instruction = instruction::add_vote(
+            &program_id,
+            &vote_keypair.pubkey(),
+            &voter.pubkey(),
+            i as u8,
+        );
+        let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
+        tx.sign(&[&payer, voter], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        let account = bank_clients.get_account(ballot).await.unwrap().unwrap();
+        let ballot_data = BallotAccount::try_from_slice(&account.data).unwrap();
+        assert_eq!(ballot_data.option, i as u8);
+    }
+
+    let account = bank_clients
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::try_from_slice(&account.data).unwrap();
+    assert!(vote.options.iter().all(|option| option.votes == 1));
+
+    // an index past the last option is rejected
+    let voter = Keypair::new();
+    fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+    let add_vote_instruction = instruction::add_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &voter.pubkey(),
+        labels.len() as u8,
+    );
+    let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::InvalidOptionIndex as u32)
+        )
+    );
+
+    // removing a ballot only takes it off the option it was cast for
+    let remove_vote_instruction =
+        instruction::remove_vote(&program_id, &vote_keypair.pubkey(), &voters[1].pubkey());
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voters[1]], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let account = bank_clients
+        .get_account(vote_keypair.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vote = VoteAccount::try_from_slice(&account.data).unwrap();
+    let tallies: Vec<u64> = vote.options.iter().map(|option| option.votes).collect();
+    assert_eq!(tallies, vec![1, 0, 1]);
+}
+
+#[test]
+fn test_error_codes() {
+    // the codes are part of the program interface, clients match on them
+    let expected = [
+        (CustomError::MissingOwnerSignature, 0),
+        (CustomError::Unauthorized, 1),
+        (CustomError::MissingVoterSignature, 2),
+        (CustomError::InvalidBallotAccount, 3),
+        (CustomError::AlreadyVoted, 4),
+        (CustomError::BallotNotFound, 5),
+        (CustomError::InvalidVoteWindow, 6),
+        (CustomError::VoteNotStarted, 7),
+        (CustomError::VoteClosed, 8),
+        (CustomError::InvalidOptionCount, 9),
+        (CustomError::InvalidOptionLabel, 10),
+        (CustomError::InvalidOptionIndex, 11),
+        (CustomError::Overflow, 12),
+        (CustomError::Underflow, 13),
+        (CustomError::InvalidAccountDiscriminator, 14),
+        (CustomError::UnsupportedAccountVersion, 15),
+        (CustomError::AlreadyMigrated, 16),
+        (CustomError::AccountNotWritable, 17),
+        (CustomError::DuplicateAccount, 18),
+    ];
+    assert_eq!(CustomError::ALL.len(), expected.len());
+    for (error, code) in expected {
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        assert_eq!(CustomError::from_code(code), Some(error));
+    }
+    assert_eq!(CustomError::from_code(expected.len() as u32), None);
+}
+
+#[test]
+fn test_account_size() {
+    // sizes drive account allocation, they must match the serialized layout byte for byte
+    for labels in [
+        vec![],
+        vec!["yes".to_string()],
+        vec!["".to_string(), "x".repeat(MAX_OPTION_LABEL_LEN)],
+        (0..MAX_OPTIONS).map(|i| "option".repeat(i)).collect(),
+    ] {
+        let vote_data = VoteAccount::new(
+            Pubkey::new_unique(),
+            i64::MIN,
+            i64::MAX,
+            0,
+            labels
+                .into_iter()
+                .map(|label| VoteOption {
+                    label,
+                    votes: u64::MAX,
+                })
+                .collect(),
+        );
+        for option in &vote_data.options {
+            assert_eq!(
+                option.serialized_len(),
+                borsh::to_vec(option).unwrap().len()
+            );
+        }
+        let data = borsh::to_vec(&vote_data).unwrap();
+        assert_eq!(vote_data.account_size(), data.len());
+        assert_eq!(
+            data[VoteAccount::OWNER_OFFSET..VoteAccount::OWNER_OFFSET + 32],
+            vote_data.owner.to_bytes()
+        );
+    }
+
+    let ballot_data = BallotAccount::new(Pubkey::new_unique(), 0, Pubkey::new_unique(), 7, 255);
+    assert_eq!(
+        BallotAccount::LEN,
+        borsh::to_vec(&ballot_data).unwrap().len()
+    );
+
+    let legacy_data = LegacyVoteAccount {
+        owner: Pubkey::new_unique(),
+        end_date: -1,
+        score: 0,
+    };
+    assert_eq!(
+        LegacyVoteAccount::LEN,
+        borsh::to_vec(&legacy_data).unwrap().len()
+    );
+
+    // packing refuses an account whose size doesn't match the state
+    let mut data = vec![0; ballot_data.account_size() + 1];
+    assert_eq!(
+        ballot_data.pack_into(&mut data),
+        Err(ProgramError::InvalidAccountData)
+    );
+    ballot_data.pack_into(&mut data[1..]).unwrap();
+    assert_eq!(BallotAccount::unpack(&data[1..]).unwrap().option, 7);
+}
+
+#[tokio::test]
+async fn test_tally_overflow_and_underflow() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("vote_program", program_id, processor!(entrypoints));
+
+    // a poll whose first option is full and whose second option is empty, with a stale ballot
+    // pointing at the empty one
+    let vote_key = Pubkey::new_unique();
+    let voter = Keypair::new();
+    let vote_data = VoteAccount::new(
+        Pubkey::new_unique(),
+        0,
+        i64::MAX,
+        0,
+        vec![
+            VoteOption {
+                label: "full".to_string(),
+                votes: u64::MAX,
+            },
+            VoteOption {
+                label: "empty".to_string(),
+                votes: 0,
+            },
+        ],
+    );
+    program_test.add_account(
+        vote_key,
+        Account {
+            lamports: 1_000_000_000,
+            data: borsh::to_vec(&vote_data).unwrap(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let (ballot, bump) = find_ballot_address(&program_id, &vote_key, &voter.pubkey());
+    let ballot_data = BallotAccount::new(vote_key, 0, voter.pubkey(), 1, bump);
+    program_test.add_account(
+        ballot,
+        Account {
+            lamports: 1_000_000_000,
+            data: borsh::to_vec(&ballot_data).unwrap(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let (mut bank_clients, payer, recent_blockhash) = program_test.start().await;
+
+    info!("Testing tally overflow...");
+    let other_voter = Keypair::new();
+    fund(
+        &mut bank_clients,
+        &payer,
+        &other_voter.pubkey(),
+        recent_blockhash,
+    )
+    .await;
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_key, &other_voter.pubkey(), 0);
+    let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &other_voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(12))
+    );
+
+    info!("Testing tally underflow...");
+    let remove_vote_instruction = instruction::remove_vote(&program_id, &vote_key, &voter.pubkey());
+    let mut tx = Transaction::new_with_payer(&[remove_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(13))
+    );
+
+    let account = bank_clients.get_account(vote_key).await.unwrap().unwrap();
+    let vote = VoteAccount::try_from_slice(&account.data).unwrap();
+    assert_eq!(vote.options, vote_data.options);
+}
+
+#[tokio::test]
+async fn test_account_discriminator() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("vote_program", program_id, processor!(entrypoints));
+
+    // a ballot owned by the program must not be mistaken for a Vote
+    let ballot_key = Pubkey::new_unique();
+    let ballot_data = BallotAccount::new(Pubkey::new_unique(), 0, Pubkey::new_unique(), 0, 255);
+    program_test.add_account(
+        ballot_key,
+        Account {
+            lamports: 1_000_000_000,
+            data: borsh::to_vec(&ballot_data).unwrap(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    // a Vote written with a layout this program doesn't know
+    let owner = Keypair::new();
+    let future_vote_key = Pubkey::new_unique();
+    let mut future_vote_data = VoteAccount::new(
+        owner.pubkey(),
+        0,
+        i64::MAX,
+        0,
+        vec![VoteOption {
+            label: "yes".to_string(),
+            votes: 0,
+        }],
+    );
+    future_vote_data.version = ACCOUNT_VERSION + 1;
+    program_test.add_account(
+        future_vote_key,
+        Account {
+            lamports: 1_000_000_000,
+            data: borsh::to_vec(&future_vote_data).unwrap(),
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let (mut bank_clients, payer, recent_blockhash) = program_test.start().await;
+    let voter = Keypair::new();
+    fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+
+    let add_vote_instruction = instruction::add_vote(&program_id, &ballot_key, &voter.pubkey(), 0);
+    let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::InvalidAccountDiscriminator as u32)
+        )
+    );
+
+    let close_vote_instruction = instruction::close_vote(
+        &program_id,
+        &future_vote_key,
+        &owner.pubkey(),
+        &owner.pubkey(),
+    );
+    let mut tx = Transaction::new_with_payer(&[close_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &owner], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::UnsupportedAccountVersion as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_vote() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("vote_program", program_id, processor!(entrypoints));
+
+    // raw bytes of Votes created by the first version of the program: owner, end_date, score
+    let owner = Keypair::new();
+    let legacy_rent = Rent::default().minimum_balance(LegacyVoteAccount::LEN);
+    let mut legacy_votes = vec![];
+    for score in [3i64, -2] {
+        let legacy_key = Pubkey::new_unique();
+        let mut data = owner.pubkey().to_bytes().to_vec();
+        // the first version always wrote an end date of -1
+        data.extend_from_slice(&(-1i64).to_le_bytes());
+        data.extend_from_slice(&score.to_le_bytes());
+        program_test.add_account(
+            legacy_key,
+            Account {
+                lamports: legacy_rent,
+                data,
+                owner: program_id,
+                ..Account::default()
+            },
+        );
+        legacy_votes.push((legacy_key, score.max(0) as u64));
+    }
+
+    let (mut bank_clients, payer, recent_blockhash) = program_test.start().await;
+    fund(&mut bank_clients, &payer, &owner.pubkey(), recent_blockhash).await;
+
+    // legacy accounts can't be used before they are migrated
+    let (legacy_key, _) = legacy_votes[0];
+    let add_vote_instruction = instruction::add_vote(&program_id, &legacy_key, &owner.pubkey(), 0);
+    let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &owner], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::InvalidAccountDiscriminator as u32)
+        )
+    );
+
+    // only the owner can migrate its Vote
+    let stranger = Keypair::new();
+    fund(
+        &mut bank_clients,
+        &payer,
+        &stranger.pubkey(),
+        recent_blockhash,
+    )
+    .await;
+    let migrate_instruction =
+        instruction::migrate_vote(&program_id, &legacy_key, &stranger.pubkey());
+    let mut tx = Transaction::new_with_payer(&[migrate_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &stranger], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::Unauthorized as u32)
+        )
+    );
+
+    let rent = bank_clients.get_rent().await.unwrap();
+    for (legacy_key, votes) in legacy_votes.iter().copied() {
+        let migrate_instruction =
+            instruction::migrate_vote(&program_id, &legacy_key, &owner.pubkey());
+        let mut tx = Transaction::new_with_payer(&[migrate_instruction], Some(&owner.pubkey()));
+        tx.sign(&[&owner], recent_blockhash);
+        bank_clients.process_transaction(tx).await.unwrap();
+
+        let account = bank_clients.get_account(legacy_key).await.unwrap().unwrap();
+        assert!(rent.is_exempt(account.lamports, account.data.len()));
+        let vote = VoteAccount::unpack(&account.data).unwrap();
+        assert_eq!(vote.owner, owner.pubkey());
+        // a -1 end date means the legacy Vote never closes
+        assert_eq!(vote.start_date, i64::MIN);
+        assert_eq!(vote.end_date, i64::MAX);
+        assert_eq!(
+            vote.options,
+            vec![VoteOption {
+                label: LegacyVoteAccount::OPTION_LABEL.to_string(),
+                votes,
+            }]
+        );
+        info!("legacy vote {} migrated", legacy_key);
+    }
+
+    // a migrated Vote takes ballots and can't be migrated twice
+    let (legacy_key, votes) = legacy_votes[0];
+    let voter = Keypair::new();
+    fund(&mut bank_clients, &payer, &voter.pubkey(), recent_blockhash).await;
+    let add_vote_instruction = instruction::add_vote(&program_id, &legacy_key, &voter.pubkey(), 0);
+    let mut tx = Transaction::new_with_payer(&[add_vote_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &voter], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+    let account = bank_clients.get_account(legacy_key).await.unwrap().unwrap();
+    assert_eq!(
+        VoteAccount::unpack(&account.data).unwrap().options[0].votes,
+        votes + 1
+    );
+
+    let migrate_instruction = instruction::migrate_vote(&program_id, &legacy_key, &owner.pubkey());
+    let mut tx = Transaction::new_with_payer(&[migrate_instruction], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &owner], recent_blockhash);
+    let err = bank_clients.process_transaction(tx).await.unwrap_err();
+    assert_eq!(
+        err.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::AlreadyMigrated as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_accounts() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("vote_program", program_id, processor!(entrypoints));
+    // the owner is not the fee payer, the runtime would make it writable otherwise
+    let owner = Keypair::new();
+    program_test.add_account(
+        owner.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::ID),
+    );
+    let (mut bank_clients, payer, recent_blockhash) = program_test.start().await;
+
+    // the owner votes on its own Vote so that RemoveVote finds a ballot
+    let vote_keypair = Keypair::new();
+    let create_instruction = instruction::create_vote(
+        &program_id,
+        &vote_keypair.pubkey(),
+        &owner.pubkey(),
+        i64::MAX,
+        None,
+        vec!["yes".to_string(), "no".to_string()],
+    );
+    let add_vote_instruction =
+        instruction::add_vote(&program_id, &vote_keypair.pubkey(), &owner.pubkey(), 0);
+    let mut tx = Transaction::new_with_payer(
+        &[create_instruction, add_vote_instruction],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer, &owner, &vote_keypair], recent_blockhash);
+    bank_clients.process_transaction(tx).await.unwrap();
+
+    let new_vote = Keypair::new();
+    let vote_key = vote_keypair.pubkey();
+    let owner_key = owner.pubkey();
+    let instructions = [
+        instruction::create_vote(
+            &program_id,
+            &new_vote.pubkey(),
+            &owner_key,
+            i64::MAX,
+            None,
+            vec!["yes".to_string(), "no".to_string()],
+        ),
+        instruction::close_vote(&program_id, &vote_key, &Pubkey::new_unique(), &owner_key),
+        instruction::add_vote(&program_id, &vote_key, &owner_key, 1),
+        instruction::remove_vote(&program_id, &vote_key, &owner_key),
+        instruction::migrate_vote(&program_id, &vote_key, &owner_key),
+    ];
+    for instruction in &instructions {
+        for (broken, expected) in
+            stubs::broken_accounts(instruction, CustomError::AccountNotWritable)
+        {
+            let signers: Vec<&Keypair> = [&owner, &new_vote]
+                .into_iter()
+                .filter(|signer| {
+                    broken
+                        .accounts
+                        .iter()
+                        .any(|meta| meta.is_signer && meta.pubkey == signer.pubkey())
+                })
+                .collect();
+            let mut tx =
+                Transaction::new_with_payer(std::slice::from_ref(&broken), Some(&payer.pubkey()));
+            tx.sign(&[&[&payer][..], &signers].concat(), recent_blockhash);
+            let err = bank_clients.process_transaction(tx).await.unwrap_err();
+            assert_eq!(
+                err.unwrap(),
+                TransactionError::InstructionError(0, expected),
+                "{:?}",
+                broken
+            );
+        }
+    }
+
+    // nothing went through
+    assert!(bank_clients
+        .get_account(new_vote.pubkey())
+        .await
+        .unwrap()
+        .is_none());
+    let account = bank_clients.get_account(vote_key).await.unwrap().unwrap();
+    let vote = VoteAccount::unpack(&account.data).unwrap();
+    assert_eq!(vote.options[0].votes, 1);
+    assert_eq!(vote.options[1].votes, 0);
+    info!("Every broken account list was rejected");
+}
+
+#[tokio::test]
+async fn test_events() {
+    setup();
+
+    let program_id = Pubkey::new_unique();
+    let (mut bank_clients, payer, recent_blockhash) =
+        ProgramTest::new("vote_program", program_id, processor!(entrypoints))
+            .start()
+            .await;
+    let vote_keypair = Keypair::new();
+    let vote_key = vote_keypair.pubkey();
+    let destination = Pubkey::new_unique();
+    let labels = vec!["yes".to_string(), "no".to_string()];
+
+    let instructions = [
+        instruction::create_vote(
+            &program_id,
+            &vote_key,
+            &payer.pubkey(),
+            i64::MAX,
+            Some(0),
+            labels.clone(),
+        ),
+        instruction::add_vote(&program_id, &vote_key, &payer.pubkey(), 1),
+        instruction::remove_vote(&program_id, &vote_key, &payer.pubkey()),
+        instruction::add_vote(&program_id, &vote_key, &payer.pubkey(), 0),
+        instruction::close_vote(&program_id, &vote_key, &destination, &payer.pubkey()),
+        // the Vote is closed, the ballot only gets its rent back
+        instruction::remove_vote(&program_id, &vote_key, &payer.pubkey()),
+    ];
+    let mut tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    tx.sign(&[&payer, &vote_keypair], recent_blockhash);
+    let result = bank_clients
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+    let logs = stubs::runtime_logs(
+        &result
+            .metadata
+            .expect("No transaction metadata")
+            .log_messages,
+    );
+    assert!(bank_clients.get_account(vote_key).await.unwrap().is_none());
+
+    let rent = bank_clients.get_rent().await.unwrap();
+    let vote_data = VoteAccount::new(
+        payer.pubkey(),
+        0,
+        i64::MAX,
+        0,
+        labels
+            .iter()
+            .map(|label| VoteOption {
+                label: label.clone(),
+                votes: 0,
+            })
+            .collect(),
+    );
+    assert_eq!(
+        VoteEvent::decode_logs(&program_id, &logs),
+        vec![
+            VoteEvent::VoteCreated(VoteCreated {
+                vote: vote_key,
+                owner: payer.pubkey(),
+                start_date: 0,
+                end_date: i64::MAX,
+                options: labels,
+            }),
+            VoteEvent::VoteCast(VoteCast {
+                vote: vote_key,
+                voter: payer.pubkey(),
+                option: 1,
+                votes: 1,
+            }),
+            VoteEvent::VoteWithdrawn(VoteWithdrawn {
+                vote: vote_key,
+                voter: payer.pubkey(),
+                option: 1,
+                votes: 0,
+            }),
+            VoteEvent::VoteCast(VoteCast {
+                vote: vote_key,
+                voter: payer.pubkey(),
+                option: 0,
+                votes: 1,
+            }),
+            VoteEvent::VoteClosed(VoteClosed {
+                vote: vote_key,
+                destination,
+                lamports: rent.minimum_balance(vote_data.account_size()),
+            }),
+            VoteEvent::BallotReclaimed(BallotReclaimed {
+                vote: vote_key,
+                voter: payer.pubkey(),
+                option: 0,
+            }),
+        ]
+    );
+    info!("Events decoded from the transaction logs");
+}